@@ -0,0 +1,369 @@
+//! Certificate based authentication for applications without a user interface, see
+//! <https://www.developer.saxo/openapi/learn/oauth-certificate-based-authentication>
+
+use super::provider::{RefreshingToken, TokenSource};
+use super::{request_token, Token, REDACTED};
+use crate::client::{Env, HttpSend, SaxoClient, Sender};
+use crate::error::SaxoError;
+
+use async_trait::async_trait;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use jsonwebtoken::{Algorithm, EncodingKey, Header};
+use serde::Serialize;
+use sha1::{Digest, Sha1};
+
+use std::fmt;
+use std::sync::Arc;
+
+/// Grant type exchanging a JWT signed with the certificate of the user for a token.
+const GRANT_TYPE: &str = "urn:saxobank:oauth:grant-type:personal-jwt";
+
+/// Lifetime of the signed assertions in seconds.
+const ASSERTION_LIFETIME: i64 = 60;
+
+/// Application and user the token is requested for.
+#[derive(Clone, Default, PartialEq, Eq)]
+pub struct Credentials {
+    pub app_key: String,
+    pub app_secret: String,
+    /// Redirect url of the application
+    pub app_url: String,
+    pub user_id: String,
+}
+
+/// The app secret is redacted, so credentials can be logged.
+impl fmt::Debug for Credentials {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Credentials")
+            .field("app_key", &self.app_key)
+            .field("app_secret", &REDACTED)
+            .field("app_url", &self.app_url)
+            .field("user_id", &self.user_id)
+            .finish()
+    }
+}
+
+#[derive(Serialize, Debug, PartialEq, Eq)]
+struct Claims<'a> {
+    iss: &'a str,
+    sub: &'a str,
+    aud: &'a str,
+    spurl: &'a str,
+    exp: i64,
+}
+
+/// Client of the Saxo SSO requesting tokens with a JWT signed by the private key of an X.509
+/// certificate issued for the user.
+pub struct CertificateFlow<S: HttpSend = Sender> {
+    client: reqwest::Client,
+    sender: S,
+    env: Env,
+    credentials: Credentials,
+    key: EncodingKey,
+    thumbprint: String,
+}
+
+impl CertificateFlow<Sender> {
+    /// Creates the flow from the PEM encoded private key and certificate.
+    pub fn new_sim(
+        credentials: Credentials,
+        private_key: &[u8],
+        certificate: &[u8],
+    ) -> Result<Self, SaxoError> {
+        Self::with_sender(Sender, credentials, private_key, certificate, Env::Sim)
+    }
+
+    /// Creates the flow from the PEM encoded private key and certificate.
+    pub fn new_live(
+        credentials: Credentials,
+        private_key: &[u8],
+        certificate: &[u8],
+    ) -> Result<Self, SaxoError> {
+        Self::with_sender(Sender, credentials, private_key, certificate, Env::Live)
+    }
+}
+
+impl<S: HttpSend> CertificateFlow<S> {
+    fn with_sender(
+        sender: S,
+        credentials: Credentials,
+        private_key: &[u8],
+        certificate: &[u8],
+        env: Env,
+    ) -> Result<Self, SaxoError> {
+        let key = EncodingKey::from_rsa_pem(private_key)
+            .map_err(|err| SaxoError::Certificate(Box::new(err)))?;
+        let certificate =
+            pem::parse(certificate).map_err(|err| SaxoError::Certificate(Box::new(err)))?;
+
+        Ok(CertificateFlow {
+            client: reqwest::Client::new(),
+            sender,
+            env,
+            credentials,
+            key,
+            thumbprint: URL_SAFE_NO_PAD.encode(Sha1::digest(certificate.contents())),
+        })
+    }
+
+    pub fn sim_with_sender(
+        sender: S,
+        credentials: Credentials,
+        private_key: &[u8],
+        certificate: &[u8],
+    ) -> Result<Self, SaxoError> {
+        Self::with_sender(sender, credentials, private_key, certificate, Env::Sim)
+    }
+
+    /// The base64url encoded SHA-1 thumbprint of the certificate, sent in the `x5t` header.
+    #[must_use]
+    pub fn thumbprint(&self) -> &str {
+        &self.thumbprint
+    }
+
+    /// Signs a new assertion for the user.
+    pub fn assertion(&self) -> Result<String, SaxoError> {
+        let mut header = Header::new(Algorithm::RS256);
+        header.x5t = Some(self.thumbprint.clone());
+
+        let claims = Claims {
+            iss: &self.credentials.app_key,
+            sub: &self.credentials.user_id,
+            aud: self.env.auth_url().trim_end_matches('/'),
+            spurl: &self.credentials.app_url,
+            exp: chrono::Utc::now().timestamp() + ASSERTION_LIFETIME,
+        };
+
+        jsonwebtoken::encode(&header, &claims, &self.key)
+            .map_err(|err| SaxoError::Certificate(Box::new(err)))
+    }
+
+    /// Exchanges a new assertion for a token.
+    pub async fn token(&self) -> Result<Token, SaxoError> {
+        let assertion = self.assertion()?;
+
+        self.request_token(&[("grant_type", GRANT_TYPE), ("assertion", &assertion)])
+            .await
+    }
+
+    /// Exchanges a refresh token for a new token.
+    pub async fn refresh(&self, refresh_token: &str) -> Result<Token, SaxoError> {
+        self.request_token(&[
+            ("grant_type", "refresh_token"),
+            ("refresh_token", refresh_token),
+        ])
+        .await
+    }
+
+    async fn request_token(&self, form: &[(&str, &str)]) -> Result<Token, SaxoError> {
+        let mut form = form.to_vec();
+        form.extend([
+            ("client_id", self.credentials.app_key.as_str()),
+            ("client_secret", self.credentials.app_secret.as_str()),
+        ]);
+
+        request_token(
+            &self.sender,
+            &self.client,
+            &format!("{}token", self.env.auth_url()),
+            &form,
+        )
+        .await
+    }
+}
+
+impl<S: HttpSend + Send + Sync + 'static> CertificateFlow<S> {
+    /// Requests a token and creates a client for the environment of the flow with it.
+    /// The token is refreshed with this flow before it expires.
+    pub async fn into_client(self) -> Result<SaxoClient, SaxoError> {
+        let token = self.token().await?;
+        let env = self.env;
+
+        SaxoClient::with_sender(Sender, Arc::new(RefreshingToken::new(self, token)), env)
+            .map_err(|err| SaxoError::HTTPError(Box::new(err)))
+    }
+}
+
+#[async_trait]
+impl<S: HttpSend + Send + Sync> TokenSource for CertificateFlow<S> {
+    async fn renew(&self, current: &Token) -> Result<Token, SaxoError> {
+        // Fall back on a new assertion if the refresh token has expired as well
+        match &current.refresh_token {
+            Some(refresh_token) => match self.refresh(refresh_token).await {
+                Ok(token) => Ok(token),
+                Err(_) => self.token().await,
+            },
+            None => self.token().await,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::client::MockHttpSend;
+    use jsonwebtoken::{DecodingKey, Validation};
+    use serde_json::json;
+
+    const PRIVATE_KEY: &[u8] = include_bytes!("../../tests/data/key.pem");
+    const PUBLIC_KEY: &[u8] = include_bytes!("../../tests/data/public.pem");
+    const CERTIFICATE: &[u8] = include_bytes!("../../tests/data/certificate.pem");
+
+    fn credentials() -> Credentials {
+        Credentials {
+            app_key: "key".to_owned(),
+            app_secret: "secret".to_owned(),
+            app_url: "http://localhost".to_owned(),
+            user_id: "user".to_owned(),
+        }
+    }
+
+    fn form(request: &reqwest::RequestBuilder) -> Vec<(String, String)> {
+        let request = request.try_clone().unwrap().build().unwrap();
+        let body = request.body().unwrap().as_bytes().unwrap();
+        url::form_urlencoded::parse(body).into_owned().collect()
+    }
+
+    #[test]
+    fn test_debug_credentials() {
+        let debug = format!("{:?}", credentials());
+
+        assert!(!debug.contains("\"secret\""));
+        assert!(debug.contains("app_secret: \"<redacted>\""));
+    }
+
+    #[test]
+    fn test_thumbprint() {
+        let flow = CertificateFlow::new_sim(credentials(), PRIVATE_KEY, CERTIFICATE).unwrap();
+
+        // openssl x509 -outform der | openssl dgst -sha1 -binary | base64url
+        assert_eq!(flow.thumbprint(), "48PybnoU4vZWRPukybVVyG_N8oY");
+    }
+
+    #[test]
+    fn test_invalid_key() {
+        assert!(matches!(
+            CertificateFlow::new_sim(credentials(), b"foo", CERTIFICATE),
+            Err(SaxoError::Certificate(_))
+        ));
+        assert!(matches!(
+            CertificateFlow::new_sim(credentials(), PRIVATE_KEY, b"foo"),
+            Err(SaxoError::Certificate(_))
+        ));
+    }
+
+    #[test]
+    fn test_assertion() {
+        let flow = CertificateFlow::new_sim(credentials(), PRIVATE_KEY, CERTIFICATE).unwrap();
+        let assertion = flow.assertion().unwrap();
+
+        let mut validation = Validation::new(Algorithm::RS256);
+        validation.set_audience(&["https://sim.logonvalidation.net"]);
+        let decoded = jsonwebtoken::decode::<serde_json::Value>(
+            &assertion,
+            &DecodingKey::from_rsa_pem(PUBLIC_KEY).unwrap(),
+            &validation,
+        )
+        .unwrap();
+
+        assert_eq!(decoded.header.x5t.as_deref(), Some(flow.thumbprint()));
+        assert_eq!(decoded.claims["iss"], "key");
+        assert_eq!(decoded.claims["sub"], "user");
+        assert_eq!(decoded.claims["spurl"], "http://localhost");
+    }
+
+    #[tokio::test]
+    async fn test_token() {
+        let mut mock_sender = MockHttpSend::new();
+        mock_sender
+            .expect_send()
+            .once()
+            .withf(|request| {
+                let form = form(request);
+                form.contains(&("grant_type".to_owned(), GRANT_TYPE.to_owned()))
+                    && form.contains(&("client_secret".to_owned(), "secret".to_owned()))
+                    && form.iter().any(|(key, _)| key == "assertion")
+            })
+            .returning(|_| {
+                Ok(reqwest::Response::from(
+                    http::Response::builder()
+                        .status(200)
+                        .body(
+                            json!({
+                                "access_token": "access",
+                                "token_type": "Bearer",
+                                "expires_in": 1200,
+                                "refresh_token": "refresh",
+                                "refresh_token_expires_in": 3600
+                            })
+                            .to_string(),
+                        )
+                        .unwrap(),
+                ))
+            });
+
+        let flow =
+            CertificateFlow::sim_with_sender(mock_sender, credentials(), PRIVATE_KEY, CERTIFICATE)
+                .unwrap();
+        let token = flow.token().await.unwrap();
+
+        assert_eq!(token.access_token, "access");
+        assert_eq!(token.refresh_token.as_deref(), Some("refresh"));
+    }
+
+    #[tokio::test]
+    async fn test_renew_expired_refresh_token() {
+        let mut mock_sender = MockHttpSend::new();
+        let mut sequence = mockall::Sequence::new();
+        mock_sender
+            .expect_send()
+            .once()
+            .in_sequence(&mut sequence)
+            .withf(|request| {
+                form(request).contains(&("grant_type".to_owned(), "refresh_token".to_owned()))
+            })
+            .returning(|_| {
+                Ok(reqwest::Response::from(
+                    http::Response::builder()
+                        .status(400)
+                        .body(json!({ "error": "invalid_grant" }).to_string())
+                        .unwrap(),
+                ))
+            });
+        mock_sender
+            .expect_send()
+            .once()
+            .in_sequence(&mut sequence)
+            .withf(|request| {
+                form(request).contains(&("grant_type".to_owned(), GRANT_TYPE.to_owned()))
+            })
+            .returning(|_| {
+                Ok(reqwest::Response::from(
+                    http::Response::builder()
+                        .status(200)
+                        .body(
+                            json!({
+                                "access_token": "new",
+                                "token_type": "Bearer",
+                                "expires_in": 1200
+                            })
+                            .to_string(),
+                        )
+                        .unwrap(),
+                ))
+            });
+
+        let flow =
+            CertificateFlow::sim_with_sender(mock_sender, credentials(), PRIVATE_KEY, CERTIFICATE)
+                .unwrap();
+        let current = Token {
+            access_token: "old".to_owned(),
+            refresh_token: Some("refresh".to_owned()),
+            ..Default::default()
+        };
+
+        assert_eq!(flow.renew(&current).await.unwrap().access_token, "new");
+    }
+}
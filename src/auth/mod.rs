@@ -0,0 +1,85 @@
+//! Authentication with the Saxo SSO, see
+//! <https://www.developer.saxo/openapi/learn/security>
+
+pub mod certificate;
+pub mod pkce;
+pub mod provider;
+
+use crate::client::HttpSend;
+use crate::error::{AuthError, SaxoError};
+
+use serde::Deserialize;
+
+use std::fmt;
+
+/// Placeholder of secrets in `Debug` output.
+const REDACTED: &str = "<redacted>";
+
+/// Response of the token end point.
+#[derive(Deserialize, Clone, Default, PartialEq, Eq)]
+pub struct Token {
+    pub access_token: String,
+    pub token_type: String,
+    /// Lifetime of the access token in seconds
+    pub expires_in: u64,
+    pub refresh_token: Option<String>,
+    /// Lifetime of the refresh token in seconds
+    pub refresh_token_expires_in: Option<u64>,
+    pub base_uri: Option<String>,
+}
+
+/// The access and refresh tokens are redacted, so tokens can be logged.
+impl fmt::Debug for Token {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Token")
+            .field("access_token", &REDACTED)
+            .field("token_type", &self.token_type)
+            .field("expires_in", &self.expires_in)
+            .field(
+                "refresh_token",
+                &self.refresh_token.as_ref().map(|_| REDACTED),
+            )
+            .field("refresh_token_expires_in", &self.refresh_token_expires_in)
+            .field("base_uri", &self.base_uri)
+            .finish()
+    }
+}
+
+/// Posts the form to the token end point at `url`.
+pub(crate) async fn request_token<S: HttpSend>(
+    sender: &S,
+    client: &reqwest::Client,
+    url: &str,
+    form: &[(&str, &str)],
+) -> Result<Token, SaxoError> {
+    let response = sender.send(client.post(url).form(form)).await?;
+
+    if response.status().is_success() {
+        Ok(response.json::<Token>().await?)
+    } else {
+        // Failed token requests contain an OAuth error body
+        Err(SaxoError::Auth(
+            response.json::<AuthError>().await.unwrap_or_default(),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_debug_token() {
+        let token = Token {
+            access_token: "secret-access".to_owned(),
+            token_type: "Bearer".to_owned(),
+            expires_in: 1200,
+            refresh_token: Some("secret-refresh".to_owned()),
+            ..Default::default()
+        };
+
+        let debug = format!("{token:?}");
+        assert!(!debug.contains("secret"));
+        assert!(debug.contains("Bearer"));
+    }
+}
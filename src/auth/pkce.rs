@@ -0,0 +1,319 @@
+//! Authorization code grant with PKCE, see
+//! <https://www.developer.saxo/openapi/learn/oauth-authorization-code-grant-pkce>
+
+use super::provider::{RefreshingToken, TokenSource};
+use super::{request_token, Token, REDACTED};
+use crate::client::{Env, HttpSend, SaxoClient, Sender};
+use crate::error::{AuthError, SaxoClientError, SaxoError};
+
+use async_trait::async_trait;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use rand::distributions::{Alphanumeric, DistString};
+use sha2::{Digest, Sha256};
+use url::Url;
+
+use std::fmt;
+use std::sync::Arc;
+
+const CODE_VERIFIER_LENGTH: usize = 64;
+const STATE_LENGTH: usize = 16;
+
+/// An authorization request the user must be redirected to, along with the secrets needed to
+/// complete it.
+#[derive(Clone, PartialEq, Eq)]
+pub struct AuthorizationRequest {
+    /// The authorize url to redirect the user to
+    pub url: Url,
+    /// Must match the `state` query parameter of the redirect
+    pub state: String,
+    code_verifier: String,
+}
+
+/// The code verifier is redacted, as anyone holding it along with the code can get a token.
+impl fmt::Debug for AuthorizationRequest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AuthorizationRequest")
+            .field("url", &self.url)
+            .field("state", &self.state)
+            .field("code_verifier", &REDACTED)
+            .finish()
+    }
+}
+
+impl AuthorizationRequest {
+    #[must_use]
+    pub fn code_verifier(&self) -> &str {
+        &self.code_verifier
+    }
+}
+
+/// The S256 code challenge of a PKCE code verifier.
+#[must_use]
+pub fn code_challenge(code_verifier: &str) -> String {
+    URL_SAFE_NO_PAD.encode(Sha256::digest(code_verifier.as_bytes()))
+}
+
+/// Client of the Saxo SSO for the authorization code grant with PKCE.
+pub struct AuthorizationCodeFlow<S: HttpSend = Sender> {
+    client: reqwest::Client,
+    sender: S,
+    env: Env,
+    app_key: String,
+    redirect_uri: String,
+}
+
+impl AuthorizationCodeFlow<Sender> {
+    fn new(app_key: &str, redirect_uri: &str, env: Env) -> Self {
+        Self::with_sender(Sender, app_key, redirect_uri, env)
+    }
+
+    #[must_use]
+    pub fn new_sim(app_key: &str, redirect_uri: &str) -> Self {
+        Self::new(app_key, redirect_uri, Env::Sim)
+    }
+
+    #[must_use]
+    pub fn new_live(app_key: &str, redirect_uri: &str) -> Self {
+        Self::new(app_key, redirect_uri, Env::Live)
+    }
+}
+
+impl<S: HttpSend> AuthorizationCodeFlow<S> {
+    fn with_sender(sender: S, app_key: &str, redirect_uri: &str, env: Env) -> Self {
+        AuthorizationCodeFlow {
+            client: reqwest::Client::new(),
+            sender,
+            env,
+            app_key: app_key.to_owned(),
+            redirect_uri: redirect_uri.to_owned(),
+        }
+    }
+
+    pub fn sim_with_sender(sender: S, app_key: &str, redirect_uri: &str) -> Self {
+        Self::with_sender(sender, app_key, redirect_uri, Env::Sim)
+    }
+
+    /// Builds the authorize url with a new code verifier and state.
+    pub fn authorize(&self) -> Result<AuthorizationRequest, SaxoError> {
+        let mut rng = rand::thread_rng();
+        let code_verifier = Alphanumeric.sample_string(&mut rng, CODE_VERIFIER_LENGTH);
+        let state = Alphanumeric.sample_string(&mut rng, STATE_LENGTH);
+
+        let url = Url::parse_with_params(
+            &format!("{}authorize", self.env.auth_url()),
+            &[
+                ("response_type", "code"),
+                ("client_id", &self.app_key),
+                ("redirect_uri", &self.redirect_uri),
+                ("state", &state),
+                ("code_challenge", &code_challenge(&code_verifier)),
+                ("code_challenge_method", "S256"),
+            ],
+        )?;
+
+        Ok(AuthorizationRequest {
+            url,
+            state,
+            code_verifier,
+        })
+    }
+
+    /// Exchanges the code received on the redirect uri for a token.
+    pub async fn exchange(
+        &self,
+        request: &AuthorizationRequest,
+        code: &str,
+    ) -> Result<Token, SaxoError> {
+        self.request_token(&[
+            ("grant_type", "authorization_code"),
+            ("code", code),
+            ("redirect_uri", &self.redirect_uri),
+            ("client_id", &self.app_key),
+            ("code_verifier", &request.code_verifier),
+        ])
+        .await
+    }
+
+    /// Exchanges a refresh token for a new token.
+    pub async fn refresh(&self, refresh_token: &str) -> Result<Token, SaxoError> {
+        self.request_token(&[
+            ("grant_type", "refresh_token"),
+            ("refresh_token", refresh_token),
+            ("redirect_uri", &self.redirect_uri),
+            ("client_id", &self.app_key),
+        ])
+        .await
+    }
+
+    async fn request_token(&self, form: &[(&str, &str)]) -> Result<Token, SaxoError> {
+        request_token(
+            &self.sender,
+            &self.client,
+            &format!("{}token", self.env.auth_url()),
+            form,
+        )
+        .await
+    }
+}
+
+impl<S: HttpSend + Send + Sync + 'static> AuthorizationCodeFlow<S> {
+    /// Creates a client for the environment of the flow, authenticated with `token`.
+    /// The token is refreshed with this flow before it expires.
+    pub fn into_client(self, token: Token) -> Result<SaxoClient, SaxoClientError> {
+        let env = self.env;
+        SaxoClient::with_sender(Sender, Arc::new(RefreshingToken::new(self, token)), env)
+    }
+}
+
+#[async_trait]
+impl<S: HttpSend + Send + Sync> TokenSource for AuthorizationCodeFlow<S> {
+    async fn renew(&self, current: &Token) -> Result<Token, SaxoError> {
+        let refresh_token = current.refresh_token.as_deref().ok_or_else(|| {
+            SaxoError::Auth(AuthError {
+                error: "invalid_grant".to_owned(),
+                error_description: Some("No refresh token".to_owned()),
+            })
+        })?;
+
+        self.refresh(refresh_token).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::client::MockHttpSend;
+    use serde_json::json;
+
+    fn form(request: &reqwest::RequestBuilder) -> Vec<(String, String)> {
+        let request = request.try_clone().unwrap().build().unwrap();
+        let body = request.body().unwrap().as_bytes().unwrap();
+        url::form_urlencoded::parse(body).into_owned().collect()
+    }
+
+    #[test]
+    fn test_code_challenge() {
+        // Example of https://www.rfc-editor.org/rfc/rfc7636#appendix-B
+        assert_eq!(
+            code_challenge("dBjftJeZ4CVP-mB92K27uhbUJU1p1r_wW1gFWFOEjXk"),
+            "E9Melhoa2OwvFrEMTJguCHaoeK1t8URWbuGJSstw-cM"
+        );
+    }
+
+    #[test]
+    fn test_authorize() {
+        let flow = AuthorizationCodeFlow::new_sim("key", "http://localhost/redirect");
+        let request = flow.authorize().unwrap();
+
+        assert_eq!(request.url.host_str(), Some("sim.logonvalidation.net"));
+        assert_eq!(request.url.path(), "/authorize");
+        assert_eq!(request.code_verifier().len(), CODE_VERIFIER_LENGTH);
+
+        let query = request.url.query_pairs().into_owned().collect::<Vec<_>>();
+        assert_eq!(
+            query,
+            vec![
+                ("response_type".to_owned(), "code".to_owned()),
+                ("client_id".to_owned(), "key".to_owned()),
+                (
+                    "redirect_uri".to_owned(),
+                    "http://localhost/redirect".to_owned()
+                ),
+                ("state".to_owned(), request.state.clone()),
+                (
+                    "code_challenge".to_owned(),
+                    code_challenge(request.code_verifier())
+                ),
+                ("code_challenge_method".to_owned(), "S256".to_owned()),
+            ]
+        );
+
+        assert_ne!(flow.authorize().unwrap().state, request.state);
+    }
+
+    #[test]
+    fn test_debug_authorization_request() {
+        let flow = AuthorizationCodeFlow::new_sim("key", "http://localhost/redirect");
+        let request = flow.authorize().unwrap();
+        let debug = format!("{request:?}");
+
+        assert!(!debug.contains(request.code_verifier()));
+        assert!(debug.contains("code_verifier: \"<redacted>\""));
+    }
+
+    #[tokio::test]
+    async fn test_exchange() {
+        let mut mock_sender = MockHttpSend::new();
+        mock_sender
+            .expect_send()
+            .once()
+            .withf(|request| {
+                let form = form(request);
+                form.contains(&("grant_type".to_owned(), "authorization_code".to_owned()))
+                    && form.contains(&("code".to_owned(), "foo".to_owned()))
+                    && form.iter().any(|(key, _)| key == "code_verifier")
+            })
+            .returning(|_| {
+                Ok(reqwest::Response::from(
+                    http::Response::builder()
+                        .status(200)
+                        .body(
+                            json!({
+                                "access_token": "access",
+                                "token_type": "Bearer",
+                                "expires_in": 1200,
+                                "refresh_token": "refresh",
+                                "refresh_token_expires_in": 3600,
+                                "base_uri": null
+                            })
+                            .to_string(),
+                        )
+                        .unwrap(),
+                ))
+            });
+
+        let flow = AuthorizationCodeFlow::sim_with_sender(mock_sender, "key", "http://localhost");
+        let request = flow.authorize().unwrap();
+        let token = flow.exchange(&request, "foo").await.unwrap();
+
+        assert_eq!(token.access_token, "access");
+        assert_eq!(token.expires_in, 1200);
+        assert_eq!(token.refresh_token.as_deref(), Some("refresh"));
+        assert!(flow.into_client(token).is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_exchange_invalid_grant() {
+        let mut mock_sender = MockHttpSend::new();
+        mock_sender.expect_send().once().returning(|_| {
+            Ok(reqwest::Response::from(
+                http::Response::builder()
+                    .status(400)
+                    .body(
+                        json!({
+                            "error": "invalid_grant",
+                            "error_description": "Code expired"
+                        })
+                        .to_string(),
+                    )
+                    .unwrap(),
+            ))
+        });
+
+        let flow = AuthorizationCodeFlow::sim_with_sender(mock_sender, "key", "http://localhost");
+        let request = flow.authorize().unwrap();
+
+        match flow.exchange(&request, "foo").await {
+            Err(SaxoError::Auth(err)) => assert_eq!(
+                err,
+                AuthError {
+                    error: "invalid_grant".to_owned(),
+                    error_description: Some("Code expired".to_owned()),
+                }
+            ),
+            _ => panic!("expected SaxoError::Auth"),
+        }
+    }
+}
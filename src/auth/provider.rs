@@ -0,0 +1,178 @@
+//! Access tokens consulted by the client for every request, see
+//! <https://www.developer.saxo/openapi/learn/security>
+
+use super::Token;
+use crate::error::SaxoError;
+
+use async_trait::async_trait;
+use mockall::automock;
+use tokio::sync::Mutex;
+
+use std::time::{Duration, Instant};
+
+/// Margin before expiry at which tokens are refreshed by default.
+const DEFAULT_REFRESH_MARGIN: Duration = Duration::from_mins(1);
+
+/// Provides the access token authorizing requests.
+#[automock]
+#[async_trait]
+pub trait TokenProvider: Send + Sync {
+    /// A valid access token, refreshed first if it is about to expire.
+    async fn access_token(&self) -> Result<String, SaxoError>;
+
+    /// Refreshes the access token, e.g. after it has been rejected by the server.
+    async fn refresh(&self) -> Result<String, SaxoError>;
+}
+
+/// A fixed token, e.g. a 24-hour developer token, which cannot be refreshed.
+pub struct StaticToken(String);
+
+impl StaticToken {
+    #[must_use]
+    pub fn new(token: &str) -> Self {
+        StaticToken(token.to_owned())
+    }
+}
+
+#[async_trait]
+impl TokenProvider for StaticToken {
+    async fn access_token(&self) -> Result<String, SaxoError> {
+        Ok(self.0.clone())
+    }
+
+    async fn refresh(&self) -> Result<String, SaxoError> {
+        Err(SaxoError::Unauthorized)
+    }
+}
+
+/// Issues new tokens, e.g. with a refresh token.
+#[async_trait]
+pub trait TokenSource: Send + Sync {
+    async fn renew(&self, current: &Token) -> Result<Token, SaxoError>;
+}
+
+struct Current {
+    token: Token,
+    expires_at: Instant,
+}
+
+impl From<Token> for Current {
+    fn from(token: Token) -> Self {
+        Current {
+            expires_at: Instant::now() + Duration::from_secs(token.expires_in),
+            token,
+        }
+    }
+}
+
+/// A token renewed from its source shortly before it expires.
+pub struct RefreshingToken<T: TokenSource> {
+    source: T,
+    current: Mutex<Current>,
+    margin: Duration,
+}
+
+impl<T: TokenSource> RefreshingToken<T> {
+    #[must_use]
+    pub fn new(source: T, token: Token) -> Self {
+        RefreshingToken {
+            source,
+            current: Mutex::new(token.into()),
+            margin: DEFAULT_REFRESH_MARGIN,
+        }
+    }
+
+    /// Sets how long before expiry the token is refreshed.
+    #[must_use]
+    pub fn margin(mut self, margin: Duration) -> Self {
+        self.margin = margin;
+        self
+    }
+}
+
+#[async_trait]
+impl<T: TokenSource> TokenProvider for RefreshingToken<T> {
+    async fn access_token(&self) -> Result<String, SaxoError> {
+        // Holding the lock while renewing makes concurrent requests wait for a single refresh
+        let mut current = self.current.lock().await;
+
+        if Instant::now() + self.margin >= current.expires_at {
+            *current = self.source.renew(&current.token).await?.into();
+        }
+
+        Ok(current.token.access_token.clone())
+    }
+
+    async fn refresh(&self) -> Result<String, SaxoError> {
+        let mut current = self.current.lock().await;
+        *current = self.source.renew(&current.token).await?.into();
+
+        Ok(current.token.access_token.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    struct Counter(AtomicU32);
+
+    #[async_trait]
+    impl TokenSource for Counter {
+        async fn renew(&self, current: &Token) -> Result<Token, SaxoError> {
+            let count = self.0.fetch_add(1, Ordering::SeqCst) + 1;
+            Ok(Token {
+                access_token: format!("{}-{count}", current.access_token),
+                expires_in: 1200,
+                ..Default::default()
+            })
+        }
+    }
+
+    fn token(expires_in: u64) -> Token {
+        Token {
+            access_token: "token".to_owned(),
+            expires_in,
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_static_token() {
+        let provider = StaticToken::new("foo");
+        assert_eq!(provider.access_token().await.unwrap(), "foo");
+        assert!(matches!(
+            provider.refresh().await,
+            Err(SaxoError::Unauthorized)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_refreshing_token_valid() {
+        let provider = RefreshingToken::new(Counter(AtomicU32::new(0)), token(1200));
+
+        assert_eq!(provider.access_token().await.unwrap(), "token");
+        assert_eq!(provider.access_token().await.unwrap(), "token");
+    }
+
+    #[tokio::test]
+    async fn test_refreshing_token_about_to_expire() {
+        let provider = RefreshingToken::new(Counter(AtomicU32::new(0)), token(30));
+
+        assert_eq!(provider.access_token().await.unwrap(), "token-1");
+        assert_eq!(provider.access_token().await.unwrap(), "token-1");
+
+        let provider = provider.margin(Duration::ZERO);
+        assert_eq!(provider.access_token().await.unwrap(), "token-1");
+    }
+
+    #[tokio::test]
+    async fn test_refreshing_token_refresh() {
+        let provider = RefreshingToken::new(Counter(AtomicU32::new(0)), token(1200));
+
+        assert_eq!(provider.refresh().await.unwrap(), "token-1");
+        assert_eq!(provider.access_token().await.unwrap(), "token-1");
+    }
+}
@@ -0,0 +1,204 @@
+//! In-memory cache of the static reference data, to resolve codes and ids returned by other
+//! end points without asking the server again.
+
+use crate::messages::reference_data::countries::Country;
+use crate::messages::reference_data::cultures::Culture;
+use crate::messages::reference_data::currencies::Currency;
+use crate::messages::reference_data::languages::Language;
+use crate::messages::reference_data::timezones::TimeZone;
+
+use std::collections::HashMap;
+
+/// Currencies, countries, cultures, languages and time zones, by code or id. Entries without one
+/// are left out.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct ReferenceDataCache {
+    countries: HashMap<String, Country>,
+    cultures: HashMap<String, Culture>,
+    currencies: HashMap<String, Currency>,
+    languages: HashMap<String, Language>,
+    time_zones: HashMap<i32, TimeZone>,
+}
+
+impl ReferenceDataCache {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    #[must_use]
+    pub fn with_countries(mut self, countries: Vec<Country>) -> Self {
+        self.countries.extend(
+            countries
+                .into_iter()
+                .filter_map(|country| Some((country.country_code.clone()?, country))),
+        );
+        self
+    }
+
+    #[must_use]
+    pub fn with_cultures(mut self, cultures: Vec<Culture>) -> Self {
+        self.cultures.extend(
+            cultures
+                .into_iter()
+                .filter_map(|culture| Some((culture.culture_code.clone()?, culture))),
+        );
+        self
+    }
+
+    #[must_use]
+    pub fn with_currencies(mut self, currencies: Vec<Currency>) -> Self {
+        self.currencies.extend(
+            currencies
+                .into_iter()
+                .filter_map(|currency| Some((currency.currency_code.clone()?, currency))),
+        );
+        self
+    }
+
+    #[must_use]
+    pub fn with_languages(mut self, languages: Vec<Language>) -> Self {
+        self.languages.extend(
+            languages
+                .into_iter()
+                .filter_map(|language| Some((language.language_code.clone()?, language))),
+        );
+        self
+    }
+
+    #[must_use]
+    pub fn with_time_zones(mut self, time_zones: Vec<TimeZone>) -> Self {
+        self.time_zones.extend(
+            time_zones
+                .into_iter()
+                .filter_map(|time_zone| Some((time_zone.time_zone_id?, time_zone))),
+        );
+        self
+    }
+
+    /// Country by its ISO 3166 alpha-2 code, e.g. `DK`.
+    #[must_use]
+    pub fn country(&self, country_code: &str) -> Option<&Country> {
+        self.countries.get(country_code)
+    }
+
+    /// Culture by its code, e.g. `da-DK`.
+    #[must_use]
+    pub fn culture(&self, culture_code: &str) -> Option<&Culture> {
+        self.cultures.get(culture_code)
+    }
+
+    /// Currency by its ISO 4217 code, e.g. `EUR`.
+    #[must_use]
+    pub fn currency(&self, currency_code: &str) -> Option<&Currency> {
+        self.currencies.get(currency_code)
+    }
+
+    /// Number of decimals of amounts in the currency.
+    #[must_use]
+    pub fn currency_decimals(&self, currency_code: &str) -> Option<i32> {
+        self.currency(currency_code)?.decimals
+    }
+
+    /// Language by its ISO 639-1 code, e.g. `da`.
+    #[must_use]
+    pub fn language(&self, language_code: &str) -> Option<&Language> {
+        self.languages.get(language_code)
+    }
+
+    /// Time zone by its id, e.g. the `TimeZoneId` of a user.
+    #[must_use]
+    pub fn time_zone(&self, time_zone_id: i32) -> Option<&TimeZone> {
+        self.time_zones.get(&time_zone_id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::messages::reference_data::{countries, cultures, currencies, languages, timezones};
+    use serde_json::json;
+
+    #[test]
+    fn test_lookups() {
+        let currencies = json!({
+            "Data": [
+                { "CurrencyCode": "EUR", "Decimals": 2, "Name": "Euro", "Symbol": "€" },
+                { "CurrencyCode": "JPY", "Decimals": 0, "Name": "Japanese Yen" },
+                { "Name": "No code" }
+            ]
+        });
+        let countries = json!({
+            "Data": [
+                {
+                    "A3": "DNK",
+                    "CountryCode": "DK",
+                    "DisplayName": "Denmark",
+                    "Name": "Denmark",
+                    "NumericCode": 208
+                }
+            ]
+        });
+        let cultures = json!({
+            "Data": [{ "CultureCode": "da-DK", "Name": "Danish (Denmark)" }]
+        });
+        let languages = json!({
+            "Data": [{ "LanguageCode": "da", "LanguageName": "Danish", "NativeName": "Dansk" }]
+        });
+        let time_zones = json!({
+            "Data": [
+                { "DisplayName": "GMT+1 Copenhagen", "TimeZoneId": 2, "ZoneName": "Europe/Copenhagen" }
+            ]
+        });
+
+        let cache = ReferenceDataCache::new()
+            .with_countries(
+                serde_json::from_value::<countries::Response>(countries)
+                    .unwrap()
+                    .data,
+            )
+            .with_cultures(
+                serde_json::from_value::<cultures::Response>(cultures)
+                    .unwrap()
+                    .data,
+            )
+            .with_languages(
+                serde_json::from_value::<languages::Response>(languages)
+                    .unwrap()
+                    .data,
+            )
+            .with_currencies(
+                serde_json::from_value::<currencies::Response>(currencies)
+                    .unwrap()
+                    .data,
+            )
+            .with_time_zones(
+                serde_json::from_value::<timezones::Response>(time_zones)
+                    .unwrap()
+                    .data,
+            );
+
+        assert_eq!(cache.currency_decimals("EUR"), Some(2));
+        assert_eq!(cache.currency_decimals("JPY"), Some(0));
+        assert_eq!(cache.currency_decimals("USD"), None);
+        assert_eq!(cache.currencies.len(), 2);
+        assert_eq!(
+            cache
+                .time_zone(2)
+                .and_then(|zone| zone.zone_name.as_deref()),
+            Some("Europe/Copenhagen")
+        );
+        let country = cache.country("DK").unwrap();
+        assert_eq!(country.a3.as_deref(), Some("DNK"));
+        assert_eq!(country.numeric_code, Some(208));
+        assert!(cache.country("SE").is_none());
+        assert_eq!(
+            cache.culture("da-DK").unwrap().name.as_deref(),
+            Some("Danish (Denmark)")
+        );
+        let language = cache.language("da").unwrap();
+        assert_eq!(language.language_name.as_deref(), Some("Danish"));
+        assert_eq!(language.native_name.as_deref(), Some("Dansk"));
+    }
+}
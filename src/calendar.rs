@@ -0,0 +1,297 @@
+//! Trading calendar of exchanges, computed locally from the sessions returned by
+//! <https://www.developer.saxo/openapi/referencedocs/ref/v1/exchanges>
+
+use crate::messages::reference_data::exchanges::{self, ExchangeSession, ExchangeSessionState};
+
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+
+use std::collections::HashMap;
+
+/// Sessions of a single exchange, sorted by start time.
+#[derive(Debug, Default, Clone, PartialEq)]
+struct Schedule {
+    all_day: bool,
+    time_zone_offset: Duration,
+    sessions: Vec<ExchangeSession>,
+}
+
+impl Schedule {
+    fn is_open(session: &ExchangeSession) -> bool {
+        session.state.is_some_and(ExchangeSessionState::is_open)
+    }
+
+    /// Whether the exchange is open right before `session` starts.
+    fn open_before(&self, index: usize) -> bool {
+        index > 0 && {
+            let previous = &self.sessions[index - 1];
+            Self::is_open(previous) && previous.end_time == self.sessions[index].start_time
+        }
+    }
+
+    /// Whether the exchange is open right after `session` ends.
+    fn open_after(&self, index: usize) -> bool {
+        self.sessions.get(index + 1).is_some_and(|next| {
+            Self::is_open(next) && next.start_time == self.sessions[index].end_time
+        })
+    }
+}
+
+/// Opening and closing times of exchanges, identified by their `ExchangeId`.
+///
+/// The server only sends the sessions of the next few days, so the calendar doesn't know about
+/// anything outside of them. Local days use the current time zone offset of the exchange, so
+/// they are off by the daylight saving shift on the other side of a change. Exchanges open all
+/// day have no openings or closings.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct ExchangeCalendar {
+    exchanges: HashMap<String, Schedule>,
+}
+
+impl ExchangeCalendar {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds or replaces the sessions of an exchange. Exchanges without an id are ignored.
+    pub fn insert(&mut self, exchange: exchanges::ResponseData) {
+        let Some(exchange_id) = exchange.exchange_id else {
+            return;
+        };
+        let mut sessions: Vec<_> = exchange
+            .exchange_sessions
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|session| session.start_time.is_some() && session.end_time.is_some())
+            .collect();
+        sessions.sort_by_key(|session| session.start_time);
+
+        self.exchanges.insert(
+            exchange_id,
+            Schedule {
+                all_day: exchange.all_day == Some(true),
+                time_zone_offset: exchange.time_zone_offset.unwrap_or_default().duration(),
+                sessions,
+            },
+        );
+    }
+
+    /// Ids of the exchanges in the calendar.
+    pub fn exchange_ids(&self) -> impl Iterator<Item = &str> {
+        self.exchanges.keys().map(String::as_str)
+    }
+
+    /// State of the exchange at `time`. Exchanges open all day are trading outside of their
+    /// sessions.
+    #[must_use]
+    pub fn state_at(&self, exchange_id: &str, time: DateTime<Utc>) -> Option<ExchangeSessionState> {
+        let schedule = self.exchanges.get(exchange_id)?;
+
+        exchanges::session_at(&schedule.sessions, time)
+            .and_then(|session| session.state)
+            .or(schedule
+                .all_day
+                .then_some(ExchangeSessionState::AutomatedTrading))
+    }
+
+    /// Whether orders can be traded on the exchange at `time`.
+    #[must_use]
+    pub fn is_open_at(&self, exchange_id: &str, time: DateTime<Utc>) -> bool {
+        self.state_at(exchange_id, time)
+            .is_some_and(ExchangeSessionState::is_open)
+    }
+
+    /// First time at or after `time` at which the exchange opens. If the exchange is open at
+    /// `time`, this is the opening following its next close. Consecutive open sessions, e.g.
+    /// pre-market and normal trading, count as a single opening.
+    #[must_use]
+    pub fn next_open(&self, exchange_id: &str, time: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let schedule = self.exchanges.get(exchange_id)?;
+
+        schedule
+            .sessions
+            .iter()
+            .enumerate()
+            .filter(|(_, session)| Schedule::is_open(session))
+            .filter(|(index, _)| !schedule.open_before(*index))
+            .find_map(|(_, session)| session.start_time.filter(|start| *start >= time))
+    }
+
+    /// First time after `time` at which the exchange closes, which is the end of the current
+    /// opening if the exchange is open at `time`.
+    #[must_use]
+    pub fn next_close(&self, exchange_id: &str, time: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let schedule = self.exchanges.get(exchange_id)?;
+
+        schedule
+            .sessions
+            .iter()
+            .enumerate()
+            .filter(|(_, session)| Schedule::is_open(session))
+            .filter(|(index, _)| !schedule.open_after(*index))
+            .find_map(|(_, session)| session.end_time.filter(|end| *end > time))
+    }
+
+    /// Sessions overlapping the days from `from` to `to` included, in the local time of the
+    /// exchange. The days are bounded with the current offset of the exchange, which doesn't
+    /// account for a daylight saving change within the range.
+    #[must_use]
+    pub fn sessions_between(
+        &self,
+        exchange_id: &str,
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> Vec<&ExchangeSession> {
+        let Some(schedule) = self.exchanges.get(exchange_id) else {
+            return Vec::new();
+        };
+        let local_midnight = |date: NaiveDate| {
+            date.and_time(chrono::NaiveTime::MIN).and_utc() - schedule.time_zone_offset
+        };
+        let start = local_midnight(from);
+        let end = local_midnight(to) + Duration::days(1);
+
+        schedule
+            .sessions
+            .iter()
+            .filter(|session| {
+                matches!(
+                    (session.start_time, session.end_time),
+                    (Some(session_start), Some(session_end))
+                        if session_start < end && session_end > start
+                )
+            })
+            .collect()
+    }
+}
+
+impl FromIterator<exchanges::ResponseData> for ExchangeCalendar {
+    fn from_iter<T: IntoIterator<Item = exchanges::ResponseData>>(iter: T) -> Self {
+        let mut calendar = Self::new();
+        for exchange in iter {
+            calendar.insert(exchange);
+        }
+        calendar
+    }
+}
+
+impl From<exchanges::Response> for ExchangeCalendar {
+    fn from(response: exchanges::Response) -> Self {
+        response.data.into_iter().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use serde_json::json;
+
+    fn calendar() -> ExchangeCalendar {
+        let session =
+            |start, end, state| json!({ "StartTime": start, "EndTime": end, "State": state });
+        let response = json!({
+            "Data": [
+                {
+                    "ExchangeId": "NYSE",
+                    "ExchangeSessions": [
+                        session("2023-06-01T20:00:00Z", "2023-06-02T08:00:00Z", "Closed"),
+                        session("2023-06-01T08:00:00Z", "2023-06-01T13:30:00Z", "PreTrading"),
+                        session("2023-06-01T13:30:00Z", "2023-06-01T20:00:00Z", "AutomatedTrading"),
+                        session("2023-06-02T08:00:00Z", "2023-06-02T13:30:00Z", "PreTrading"),
+                        session("2023-06-02T13:30:00Z", "2023-06-02T20:00:00Z", "AutomatedTrading"),
+                        session("2023-06-02T20:00:00Z", "2023-06-05T08:00:00Z", "Closed")
+                    ],
+                    "TimeZoneOffset": "-04:00:00"
+                },
+                {
+                    "AllDay": true,
+                    "ExchangeId": "SBFX"
+                }
+            ]
+        });
+
+        serde_json::from_value::<exchanges::Response>(response)
+            .unwrap()
+            .into()
+    }
+
+    fn time(time: &str) -> DateTime<Utc> {
+        time.parse().unwrap()
+    }
+
+    #[test]
+    fn test_state_at() {
+        let calendar = calendar();
+
+        assert_eq!(
+            calendar.state_at("NYSE", time("2023-06-01T14:00:00Z")),
+            Some(ExchangeSessionState::AutomatedTrading)
+        );
+        assert_eq!(
+            calendar.state_at("NYSE", time("2023-06-03T12:00:00Z")),
+            Some(ExchangeSessionState::Closed)
+        );
+        assert_eq!(
+            calendar.state_at("NYSE", time("2023-06-06T12:00:00Z")),
+            None
+        );
+        assert_eq!(
+            calendar.state_at("XETR", time("2023-06-01T14:00:00Z")),
+            None
+        );
+        assert!(calendar.is_open_at("SBFX", time("2023-06-03T12:00:00Z")));
+        assert!(!calendar.is_open_at("NYSE", time("2023-06-03T12:00:00Z")));
+    }
+
+    #[test]
+    fn test_next_open_and_close() {
+        let calendar = calendar();
+
+        // Open in pre-trading, so the next opening is the following day
+        assert_eq!(
+            calendar.next_open("NYSE", time("2023-06-01T09:00:00Z")),
+            Some(time("2023-06-02T08:00:00Z"))
+        );
+        assert_eq!(
+            calendar.next_close("NYSE", time("2023-06-01T09:00:00Z")),
+            Some(time("2023-06-01T20:00:00Z"))
+        );
+        assert_eq!(
+            calendar.next_open("NYSE", time("2023-06-01T20:00:00Z")),
+            Some(time("2023-06-02T08:00:00Z"))
+        );
+        assert_eq!(
+            calendar.next_close("NYSE", time("2023-06-02T20:00:00Z")),
+            None
+        );
+        assert_eq!(
+            calendar.next_open("SBFX", time("2023-06-01T09:00:00Z")),
+            None
+        );
+    }
+
+    #[test]
+    fn test_sessions_between() {
+        let calendar = calendar();
+        let date = |date: &str| date.parse::<NaiveDate>().unwrap();
+
+        // The local day of June 2nd starts at 04:00 UTC
+        let sessions = calendar.sessions_between("NYSE", date("2023-06-02"), date("2023-06-02"));
+        assert_eq!(
+            sessions
+                .iter()
+                .map(|session| session.start_time.unwrap())
+                .collect::<Vec<_>>(),
+            vec![
+                time("2023-06-01T20:00:00Z"),
+                time("2023-06-02T08:00:00Z"),
+                time("2023-06-02T13:30:00Z"),
+                time("2023-06-02T20:00:00Z")
+            ]
+        );
+        assert!(calendar
+            .sessions_between("NYSE", date("2023-06-10"), date("2023-06-11"))
+            .is_empty());
+    }
+}
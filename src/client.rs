@@ -1,22 +1,63 @@
+use crate::auth::provider::{StaticToken, TokenProvider};
+use crate::cache::ReferenceDataCache;
 use crate::error::{SaxoBadRequest, SaxoClientError, SaxoError};
-use crate::messages::{portfolio, reference_data};
+use crate::messages::common::{AssetType, FieldGroups};
+use crate::messages::{portfolio, reference_data, trading};
+use crate::streaming::quotes::QuoteStream;
+use crate::streaming::subscription::{
+    Subscriber, Subscription, SubscriptionResponse, SubscriptionUpdate,
+};
+use crate::streaming::StreamingConnection;
 use crate::{ODataParams, SaxoRequest, SaxoResponse, SaxoResponseOData};
 
 use async_trait::async_trait;
 use mockall::automock;
 use reqwest::header::{HeaderMap, HeaderValue};
 use serde::de::DeserializeOwned;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
+use tokio::sync::mpsc::UnboundedReceiver;
+use url::Url;
 
 use std::error::Error;
 use std::fmt;
+use std::sync::Arc;
 
-#[derive(Clone, Copy)]
-enum Env {
+/// Environment of the Saxo `OpenAPI`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Env {
     Sim,
     Live,
 }
 
+impl Env {
+    /// Base url of the Saxo SSO.
+    #[must_use]
+    pub fn auth_url(self) -> &'static str {
+        match self {
+            Env::Sim => "https://sim.logonvalidation.net/",
+            Env::Live => "https://live.logonvalidation.net/",
+        }
+    }
+
+    /// Base url of the REST end points.
+    #[must_use]
+    pub fn gateway_url(self) -> &'static str {
+        match self {
+            Env::Sim => "https://gateway.saxobank.com/sim/openapi/",
+            Env::Live => "https://gateway.saxobank.com/openapi/",
+        }
+    }
+
+    /// Base url of the streaming WebSocket.
+    #[must_use]
+    pub fn streaming_url(self) -> &'static str {
+        match self {
+            Env::Sim => "wss://streaming.saxobank.com/sim/openapi/",
+            Env::Live => "wss://streaming.saxobank.com/openapi/",
+        }
+    }
+}
+
 impl From<Env> for String {
     fn from(env: Env) -> Self {
         match env {
@@ -32,7 +73,7 @@ pub trait HttpSend {
     async fn send(
         &self,
         request: reqwest::RequestBuilder,
-    ) -> Result<reqwest::Response, Box<dyn Error>>;
+    ) -> Result<reqwest::Response, Box<dyn Error + Send + Sync>>;
 }
 
 pub struct Sender;
@@ -42,78 +83,111 @@ impl HttpSend for Sender {
     async fn send(
         &self,
         request: reqwest::RequestBuilder,
-    ) -> Result<reqwest::Response, Box<dyn Error>> {
+    ) -> Result<reqwest::Response, Box<dyn Error + Send + Sync>> {
         Ok(request.send().await?)
     }
 }
 
+/// Default value of the User-Agent header.
+const USER_AGENT: &str = concat!(env!("CARGO_PKG_NAME"), "/", env!("CARGO_PKG_VERSION"));
+
 pub struct SaxoClient<S: HttpSend = Sender> {
     client: reqwest::Client,
     sender: S,
-    env: Env,
+    base_url: Url,
+    streaming_url: Url,
+    token: Arc<dyn TokenProvider>,
+    precheck_orders: bool,
 }
 
 impl SaxoClient<Sender> {
-    fn new(token: &str, env: Env) -> Result<Self, SaxoClientError> {
-        Ok(SaxoClient {
-            client: Self::build_client(token)?,
-            sender: Sender,
-            env,
-        })
+    /// Creates a builder for a client authenticated with `token`.
+    #[must_use]
+    pub fn builder(token: Arc<dyn TokenProvider>) -> SaxoClientBuilder {
+        SaxoClientBuilder::new(token)
+    }
+
+    fn new(token: Arc<dyn TokenProvider>, env: Env) -> Result<Self, SaxoClientError> {
+        SaxoClient::with_sender(Sender, token, env)
     }
 
     pub fn new_sim(token: &str) -> Result<Self, SaxoClientError> {
-        Self::new(token, Env::Sim)
+        Self::new(Arc::new(StaticToken::new(token)), Env::Sim)
     }
 
     pub fn new_live(token: &str) -> Result<Self, SaxoClientError> {
+        Self::new(Arc::new(StaticToken::new(token)), Env::Live)
+    }
+
+    pub fn sim_with_token_provider(token: Arc<dyn TokenProvider>) -> Result<Self, SaxoClientError> {
+        Self::new(token, Env::Sim)
+    }
+
+    pub fn live_with_token_provider(
+        token: Arc<dyn TokenProvider>,
+    ) -> Result<Self, SaxoClientError> {
         Self::new(token, Env::Live)
     }
 }
 
 impl<S: HttpSend> SaxoClient<S> {
+    pub(crate) fn with_sender(
+        sender: S,
+        token: Arc<dyn TokenProvider>,
+        env: Env,
+    ) -> Result<Self, SaxoClientError> {
+        SaxoClientBuilder::new(token)
+            .env(env)
+            .sender(sender)
+            .build()
+    }
+
     pub fn sim_with_sender(sender: S, token: &str) -> Result<Self, SaxoClientError> {
-        Ok(SaxoClient {
-            client: Self::build_client(token)?,
-            sender,
-            env: Env::Sim,
-        })
+        Self::with_sender(sender, Arc::new(StaticToken::new(token)), Env::Sim)
     }
 
-    fn build_client(token: &str) -> reqwest::Result<reqwest::Client> {
-        let headers = Self::create_headers(token);
+    /// Base url the paths of the REST end points are joined to.
+    #[must_use]
+    pub fn base_url(&self) -> &Url {
+        &self.base_url
+    }
 
-        reqwest::ClientBuilder::new()
-            .default_headers(headers)
-            .build()
+    /// Sends the request built by `request` with the current access token. If the token is
+    /// rejected, it is refreshed and the request is sent once more.
+    async fn send<F>(&self, request: F) -> Result<reqwest::Response, SaxoError>
+    where
+        F: Fn() -> reqwest::RequestBuilder,
+    {
+        let token = self.token.access_token().await?;
+        let response = self
+            .sender
+            .send(request().header("Authorization", format!("BEARER {token}")))
+            .await?;
+
+        if response.status() != reqwest::StatusCode::UNAUTHORIZED {
+            return Ok(response);
+        }
+
+        match self.token.refresh().await {
+            Ok(token) => Ok(self
+                .sender
+                .send(request().header("Authorization", format!("BEARER {token}")))
+                .await?),
+            // Tokens that cannot be refreshed leave the request unauthorized
+            Err(_) => Ok(response),
+        }
     }
 
-    fn create_headers(token: &str) -> HeaderMap {
-        let mut headers = HeaderMap::new();
-        headers.insert("Accept", HeaderValue::from_static("*/*"));
-        headers.insert(
-            "Authorization",
-            HeaderValue::from_str(format!("BEARER {token}").as_str())
-                .unwrap_or(HeaderValue::from_static("*/*")),
-        );
-        headers.insert("Content-Type", HeaderValue::from_static("application/json"));
-        headers
+    fn url(&self, path: &str) -> Result<Url, SaxoError> {
+        Ok(self.base_url.join(path)?)
     }
 
     async fn get<T: SaxoRequest>(&self, request: T) -> Result<T::ResponseType, SaxoError>
     where
         <T as SaxoRequest>::ResponseType: DeserializeOwned,
     {
-        let env = String::from(self.env);
-        let response = self
-            .sender
-            .send(self.client.get(format!(
-                "https://gateway.saxobank.com/{}/openapi/{}{}", // TODO: make configurable and use .join instead
-                env,
-                T::endpoint(),
-                request.argument()
-            )))
-            .await?;
+        let url = self.url(&format!("{}{}", T::endpoint(), request.argument()))?;
+        let response = self.send(|| self.client.get(url.clone())).await?;
 
         #[cfg(debug_assertions)]
         dbg!(&response);
@@ -121,12 +195,60 @@ impl<S: HttpSend> SaxoClient<S> {
         Self::parse_response::<T>(response).await
     }
 
+    async fn post<B, R>(&self, path: &str, body: &B) -> Result<R, SaxoError>
+    where
+        B: Serialize + Sync + ?Sized,
+        R: DeserializeOwned,
+    {
+        let url = self.url(path)?;
+        let response = self
+            .send(|| self.client.post(url.clone()).json(body))
+            .await?;
+
+        Self::parse_json(response).await
+    }
+
+    async fn patch<B, R>(&self, path: &str, body: &B) -> Result<R, SaxoError>
+    where
+        B: Serialize + Sync + ?Sized,
+        R: DeserializeOwned,
+    {
+        let url = self.url(path)?;
+        let response = self
+            .send(|| self.client.patch(url.clone()).json(body))
+            .await?;
+
+        Self::parse_json(response).await
+    }
+
+    async fn delete_json<R: DeserializeOwned>(&self, path: &str) -> Result<R, SaxoError> {
+        let url = self.url(path)?;
+        let response = self.send(|| self.client.delete(url.clone())).await?;
+
+        Self::parse_json(response).await
+    }
+
+    async fn delete(&self, path: &str) -> Result<(), SaxoError> {
+        let url = self.url(path)?;
+        let response = self.send(|| self.client.delete(url.clone())).await?;
+
+        Self::check_status(response).await.map(|_| ())
+    }
+
     async fn parse_response<T: SaxoRequest>(
         response: reqwest::Response,
     ) -> Result<T::ResponseType, SaxoError>
     where
         <T as SaxoRequest>::ResponseType: DeserializeOwned,
     {
+        Self::parse_json(response).await
+    }
+
+    async fn parse_json<R: DeserializeOwned>(response: reqwest::Response) -> Result<R, SaxoError> {
+        Ok(Self::check_status(response).await?.json::<R>().await?)
+    }
+
+    async fn check_status(response: reqwest::Response) -> Result<reqwest::Response, SaxoError> {
         match response.status() {
             // Bad request contains a body that needs to be serialized
             reqwest::StatusCode::BAD_REQUEST => Err(SaxoError::BadRequest(
@@ -136,10 +258,7 @@ impl<S: HttpSend> SaxoClient<S> {
 
             // Otherwise continue deserialization
             // If error > 401 return deserialized HTTP error
-            _ => Ok(response
-                .error_for_status()?
-                .json::<T::ResponseType>()
-                .await?),
+            _ => Ok(response.error_for_status()?),
         }
     }
 
@@ -155,7 +274,7 @@ impl<S: HttpSend> SaxoClient<S> {
             .await
     }
 
-    pub async fn get_port_user_info<'de>(&self) -> Result<portfolio::users::Response, SaxoError> {
+    pub async fn get_port_user_info(&self) -> Result<portfolio::users::Response, SaxoError> {
         self.get(portfolio::users::Request::new("me")).await
     }
 
@@ -163,6 +282,486 @@ impl<S: HttpSend> SaxoClient<S> {
         self.get(portfolio::clients::Request::new("me")).await
     }
 
+    /// Accounts of the logged in user.
+    pub async fn get_port_accounts_me(
+        &self,
+    ) -> Result<portfolio::accounts::list::Response, SaxoError> {
+        self.get(portfolio::accounts::list::Request::new("me"))
+            .await
+    }
+
+    /// Accounts of the client with the given key.
+    pub async fn get_port_accounts(
+        &self,
+        client_key: &str,
+    ) -> Result<portfolio::accounts::list::Response, SaxoError> {
+        self.get(portfolio::accounts::list::Request::with_query(
+            "",
+            &[("ClientKey", client_key)],
+        ))
+        .await
+    }
+
+    pub async fn get_port_account(
+        &self,
+        account_key: &str,
+    ) -> Result<portfolio::accounts::Response, SaxoError> {
+        self.get(portfolio::accounts::Request::new(account_key))
+            .await
+    }
+
+    pub async fn get_port_balances_me(&self) -> Result<portfolio::balances::Response, SaxoError> {
+        self.get(portfolio::balances::Request::new("me")).await
+    }
+
+    pub async fn get_port_client_balances(
+        &self,
+        client_key: &str,
+    ) -> Result<portfolio::balances::Response, SaxoError> {
+        self.get(portfolio::balances::Request::with_query(
+            "",
+            &[("ClientKey", client_key)],
+        ))
+        .await
+    }
+
+    pub async fn get_port_account_balances(
+        &self,
+        client_key: &str,
+        account_key: &str,
+    ) -> Result<portfolio::balances::Response, SaxoError> {
+        self.get(portfolio::balances::Request::with_query(
+            "",
+            &[("ClientKey", client_key), ("AccountKey", account_key)],
+        ))
+        .await
+    }
+
+    pub async fn get_port_account_group_balances(
+        &self,
+        client_key: &str,
+        account_group_key: &str,
+    ) -> Result<portfolio::balances::Response, SaxoError> {
+        self.get(portfolio::balances::Request::with_query(
+            "",
+            &[
+                ("ClientKey", client_key),
+                ("AccountGroupKey", account_group_key),
+            ],
+        ))
+        .await
+    }
+
+    /// Positions of the logged in user.
+    pub async fn get_port_positions_me(
+        &self,
+        field_groups: &FieldGroups<portfolio::positions::FieldGroup>,
+    ) -> Result<portfolio::positions::list::Response, SaxoError> {
+        self.get(portfolio::positions::list::Request::with_query(
+            "me",
+            &[("FieldGroups", &field_groups.to_string())],
+        ))
+        .await
+    }
+
+    /// Positions of a client, or of a single account if `account_key` is given.
+    pub async fn get_port_positions(
+        &self,
+        client_key: &str,
+        account_key: Option<&str>,
+        field_groups: &FieldGroups<portfolio::positions::FieldGroup>,
+    ) -> Result<portfolio::positions::list::Response, SaxoError> {
+        let field_groups = field_groups.to_string();
+        let mut params = vec![("ClientKey", client_key), ("FieldGroups", &field_groups)];
+        if let Some(account_key) = account_key {
+            params.push(("AccountKey", account_key));
+        }
+
+        self.get(portfolio::positions::list::Request::with_query("", &params))
+            .await
+    }
+
+    pub async fn get_port_position(
+        &self,
+        position_id: &str,
+        client_key: &str,
+        field_groups: &FieldGroups<portfolio::positions::FieldGroup>,
+    ) -> Result<portfolio::positions::Response, SaxoError> {
+        self.get(portfolio::positions::Request::with_query(
+            position_id,
+            &[
+                ("ClientKey", client_key),
+                ("FieldGroups", &field_groups.to_string()),
+            ],
+        ))
+        .await
+    }
+
+    /// Net positions of the logged in user.
+    pub async fn get_port_net_positions_me(
+        &self,
+        field_groups: &FieldGroups<portfolio::netpositions::FieldGroup>,
+    ) -> Result<portfolio::netpositions::list::Response, SaxoError> {
+        self.get(portfolio::netpositions::list::Request::with_query(
+            "me",
+            &[("FieldGroups", &field_groups.to_string())],
+        ))
+        .await
+    }
+
+    /// Net positions of a client, or of a single account if `account_key` is given.
+    pub async fn get_port_net_positions(
+        &self,
+        client_key: &str,
+        account_key: Option<&str>,
+        field_groups: &FieldGroups<portfolio::netpositions::FieldGroup>,
+    ) -> Result<portfolio::netpositions::list::Response, SaxoError> {
+        let field_groups = field_groups.to_string();
+        let mut params = vec![("ClientKey", client_key), ("FieldGroups", &field_groups)];
+        if let Some(account_key) = account_key {
+            params.push(("AccountKey", account_key));
+        }
+
+        self.get(portfolio::netpositions::list::Request::with_query(
+            "", &params,
+        ))
+        .await
+    }
+
+    pub async fn get_port_net_position(
+        &self,
+        net_position_id: &str,
+        client_key: &str,
+        field_groups: &FieldGroups<portfolio::netpositions::FieldGroup>,
+    ) -> Result<portfolio::netpositions::Response, SaxoError> {
+        self.get(portfolio::netpositions::Request::with_query(
+            net_position_id,
+            &[
+                ("ClientKey", client_key),
+                ("FieldGroups", &field_groups.to_string()),
+            ],
+        ))
+        .await
+    }
+
+    /// Closed positions of a client, or of a single account if `account_key` is given. The
+    /// following pages are requested with [`NextHandle::next`].
+    pub async fn get_port_closed_positions(
+        &self,
+        client_key: &str,
+        account_key: Option<&str>,
+        field_groups: &FieldGroups<portfolio::closedpositions::FieldGroup>,
+        params: &ODataParams,
+    ) -> Result<NextHandle<'_, S, portfolio::closedpositions::list::Response>, SaxoError> {
+        let field_groups = field_groups.to_string();
+        let top = params.top.map(|top| top.to_string());
+        let skip = params.skip.map(|skip| skip.to_string());
+
+        let mut query = vec![("ClientKey", client_key), ("FieldGroups", &field_groups)];
+        if let Some(account_key) = account_key {
+            query.push(("AccountKey", account_key));
+        }
+        if let Some(top) = &top {
+            query.push(("$top", top));
+        }
+        if let Some(skip) = &skip {
+            query.push(("$skip", skip));
+        }
+
+        let resp = self
+            .get(portfolio::closedpositions::list::Request::with_query(
+                "", &query,
+            ))
+            .await;
+        Ok(NextHandle {
+            client: self,
+            resp: resp?,
+        })
+    }
+
+    /// Working orders of the logged in user.
+    pub async fn get_port_orders_me(
+        &self,
+        field_groups: &FieldGroups<portfolio::orders::FieldGroup>,
+    ) -> Result<portfolio::orders::list::Response, SaxoError> {
+        self.get(portfolio::orders::list::Request::with_query(
+            "me",
+            &[("FieldGroups", &field_groups.to_string())],
+        ))
+        .await
+    }
+
+    /// Working orders of a client, or of a single account if `account_key` is given.
+    pub async fn get_port_orders(
+        &self,
+        client_key: &str,
+        account_key: Option<&str>,
+        field_groups: &FieldGroups<portfolio::orders::FieldGroup>,
+    ) -> Result<portfolio::orders::list::Response, SaxoError> {
+        let field_groups = field_groups.to_string();
+        let mut params = vec![("ClientKey", client_key), ("FieldGroups", &field_groups)];
+        if let Some(account_key) = account_key {
+            params.push(("AccountKey", account_key));
+        }
+
+        self.get(portfolio::orders::list::Request::with_query("", &params))
+            .await
+    }
+
+    pub async fn get_port_order(
+        &self,
+        client_key: &str,
+        order_id: &str,
+        field_groups: &FieldGroups<portfolio::orders::FieldGroup>,
+    ) -> Result<portfolio::orders::Response, SaxoError> {
+        self.get(portfolio::orders::Request::with_query(
+            &format!("{client_key}/{order_id}"),
+            &[("FieldGroups", &field_groups.to_string())],
+        ))
+        .await
+    }
+
+    /// Checks `order` without placing it, estimating its costs and margin impact.
+    pub async fn precheck_order<T: Serialize + Sync>(
+        &self,
+        order: &T,
+    ) -> Result<trading::precheck::PrecheckResponse, SaxoError> {
+        self.post(
+            trading::precheck::PRECHECK,
+            &trading::precheck::Precheck::new(order),
+        )
+        .await
+    }
+
+    pub async fn precheck_multi_leg_order(
+        &self,
+        order: &trading::multileg::MultiLegOrder,
+    ) -> Result<trading::precheck::PrecheckResponse, SaxoError> {
+        self.post(
+            trading::multileg::MULTILEG_PRECHECK,
+            &trading::precheck::Precheck::new(order),
+        )
+        .await
+    }
+
+    /// Prechecks `order` with the precheck end point at `path` if enabled with
+    /// [`SaxoClientBuilder::precheck_orders`], failing on the first violation.
+    async fn check_order<T: Serialize + Sync>(
+        &self,
+        path: &str,
+        order: &T,
+    ) -> Result<(), SaxoError> {
+        if !self.precheck_orders {
+            return Ok(());
+        }
+
+        let precheck: trading::precheck::PrecheckResponse = self
+            .post(path, &trading::precheck::Precheck::new(order))
+            .await?;
+        match precheck.violation() {
+            Some(violation) => Err(SaxoError::PrecheckFailed(violation)),
+            None => Ok(()),
+        }
+    }
+
+    pub async fn place_order(
+        &self,
+        order: &trading::orders::Order,
+    ) -> Result<trading::orders::OrderId, SaxoError> {
+        self.check_order(trading::precheck::PRECHECK, order).await?;
+        self.post(trading::orders::ORDERS, order).await
+    }
+
+    /// Places an option strategy, whose legs are filled together at its net price.
+    pub async fn place_multi_leg_order(
+        &self,
+        order: &trading::multileg::MultiLegOrder,
+    ) -> Result<trading::multileg::MultiLegOrderId, SaxoError> {
+        self.check_order(trading::multileg::MULTILEG_PRECHECK, order)
+            .await?;
+        self.post(trading::multileg::MULTILEG, order).await
+    }
+
+    /// Places an entry order along with its related orders, or a pair of one-cancels-other
+    /// orders. Returns the ids of all placed orders, the entry order first.
+    pub async fn place_related_orders(
+        &self,
+        orders: &trading::related_orders::RelatedOrders,
+    ) -> Result<Vec<String>, SaxoError> {
+        self.check_order(trading::precheck::PRECHECK, orders)
+            .await?;
+        let order_id: trading::orders::OrderId = self.post(trading::orders::ORDERS, orders).await?;
+
+        Ok(order_id.ids().into_iter().map(str::to_owned).collect())
+    }
+
+    pub async fn modify_order(
+        &self,
+        order: &trading::orders::ModifyOrder,
+    ) -> Result<trading::orders::OrderId, SaxoError> {
+        self.patch(trading::orders::ORDERS, order).await
+    }
+
+    /// Cancels the given orders of an account.
+    pub async fn cancel_orders(
+        &self,
+        account_key: &str,
+        order_ids: &[&str],
+    ) -> Result<trading::orders::CancelledOrders, SaxoError> {
+        let query = url::form_urlencoded::Serializer::new(String::new())
+            .append_pair("AccountKey", account_key)
+            .finish();
+
+        self.delete_json(&format!(
+            "{}/{}?{query}",
+            trading::orders::ORDERS,
+            order_ids.join(",")
+        ))
+        .await
+    }
+
+    /// Snapshot of the price of an instrument.
+    pub async fn get_trade_info_price(
+        &self,
+        uic: i32,
+        asset_type: &AssetType,
+        field_groups: &FieldGroups<trading::infoprices::FieldGroup>,
+    ) -> Result<trading::infoprices::Response, SaxoError> {
+        self.get(trading::infoprices::Request::with_query(
+            "",
+            &[
+                ("AssetType", asset_type.as_str()),
+                ("FieldGroups", &field_groups.to_string()),
+                ("Uic", &uic.to_string()),
+            ],
+        ))
+        .await
+    }
+
+    /// Snapshot of the prices of several instruments, which must all be of `asset_type`.
+    pub async fn get_trade_info_prices(
+        &self,
+        uics: &[i32],
+        asset_type: &AssetType,
+        field_groups: &FieldGroups<trading::infoprices::FieldGroup>,
+    ) -> Result<trading::infoprices::list::Response, SaxoError> {
+        let uics = uics
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+            .join(",");
+
+        self.get(trading::infoprices::list::Request::with_query(
+            "",
+            &[
+                ("AssetType", asset_type.as_str()),
+                ("FieldGroups", &field_groups.to_string()),
+                ("Uics", &uics),
+            ],
+        ))
+        .await
+    }
+
+    /// Searches instruments by `keywords`, e.g. a ticker, optionally limited to the given
+    /// asset types and exchange.
+    pub async fn get_ref_instruments(
+        &self,
+        keywords: &str,
+        asset_types: &[AssetType],
+        exchange_id: Option<&str>,
+        params: &ODataParams,
+    ) -> Result<NextHandle<'_, S, reference_data::instruments::Response>, SaxoError> {
+        let asset_types = asset_types
+            .iter()
+            .map(AssetType::as_str)
+            .collect::<Vec<_>>()
+            .join(",");
+        let top = params.top.map(|top| top.to_string());
+        let skip = params.skip.map(|skip| skip.to_string());
+
+        let mut query = vec![("Keywords", keywords)];
+        if !asset_types.is_empty() {
+            query.push(("AssetTypes", &asset_types));
+        }
+        if let Some(exchange_id) = exchange_id {
+            query.push(("ExchangeId", exchange_id));
+        }
+        if let Some(top) = &top {
+            query.push(("$top", top));
+        }
+        if let Some(skip) = &skip {
+            query.push(("$skip", skip));
+        }
+
+        let resp = self
+            .get(reference_data::instruments::Request::with_query("", &query))
+            .await;
+        Ok(NextHandle {
+            client: self,
+            resp: resp?,
+        })
+    }
+
+    pub async fn get_ref_instrument_details(
+        &self,
+        uic: i32,
+        asset_type: &AssetType,
+        field_groups: &FieldGroups<reference_data::instruments::details::FieldGroup>,
+    ) -> Result<reference_data::instruments::details::Response, SaxoError> {
+        self.get(reference_data::instruments::details::Request::with_query(
+            &format!("{uic}/{asset_type}"),
+            &[("FieldGroups", &field_groups.to_string())],
+        ))
+        .await
+    }
+
+    pub async fn get_ref_countries(
+        &self,
+    ) -> Result<reference_data::countries::Response, SaxoError> {
+        self.get(reference_data::countries::Request::new("")).await
+    }
+
+    pub async fn get_ref_cultures(&self) -> Result<reference_data::cultures::Response, SaxoError> {
+        self.get(reference_data::cultures::Request::new("")).await
+    }
+
+    pub async fn get_ref_currencies(
+        &self,
+    ) -> Result<reference_data::currencies::Response, SaxoError> {
+        self.get(reference_data::currencies::Request::new("")).await
+    }
+
+    pub async fn get_ref_languages(
+        &self,
+    ) -> Result<reference_data::languages::Response, SaxoError> {
+        self.get(reference_data::languages::Request::new("")).await
+    }
+
+    pub async fn get_ref_time_zones(
+        &self,
+    ) -> Result<reference_data::timezones::Response, SaxoError> {
+        self.get(reference_data::timezones::Request::new("")).await
+    }
+
+    /// Fetches all currencies, countries, cultures, languages and time zones into a cache for
+    /// later lookups.
+    pub async fn get_ref_data_cache(&self) -> Result<ReferenceDataCache, SaxoError> {
+        let (countries, cultures, currencies, languages, time_zones) = futures_util::try_join!(
+            self.get_ref_countries(),
+            self.get_ref_cultures(),
+            self.get_ref_currencies(),
+            self.get_ref_languages(),
+            self.get_ref_time_zones(),
+        )?;
+
+        Ok(ReferenceDataCache::new()
+            .with_countries(countries.data)
+            .with_cultures(cultures.data)
+            .with_currencies(currencies.data)
+            .with_languages(languages.data)
+            .with_time_zones(time_zones.data))
+    }
+
     pub async fn get_ref_exchanges(
         &self,
         params: ODataParams,
@@ -183,12 +782,375 @@ impl<S: HttpSend> SaxoClient<S> {
     }
 }
 
+impl<S: HttpSend + Send + Sync + 'static> SaxoClient<S> {
+    /// Opens the streaming WebSocket for the given context id. Subscriptions on the connection
+    /// are created through this client.
+    pub async fn connect_streaming(
+        self: &Arc<Self>,
+        context_id: &str,
+    ) -> Result<StreamingConnection, SaxoError> {
+        StreamingConnection::connect(
+            self.streaming_url.join("streamingws/connect")?.as_str(),
+            self.token.clone(),
+            context_id,
+            self.clone(),
+        )
+        .await
+    }
+
+    /// Creates `subscription` on `connection` and parses its initial snapshot.
+    #[allow(clippy::unused_self)] // Subscriptions are created by the client of the connection
+    pub async fn create_subscription<T: DeserializeOwned>(
+        &self,
+        connection: &StreamingConnection,
+        subscription: Subscription,
+    ) -> Result<(T, UnboundedReceiver<SubscriptionUpdate>), SaxoError> {
+        let (response, receiver) = connection.create_subscription(subscription).await?;
+        let snapshot = serde_json::from_value(response.snapshot)?;
+
+        Ok((snapshot, receiver))
+    }
+
+    /// Subscribes to the accounts of a client, or to a single account if `account_key` is given.
+    pub async fn subscribe_port_accounts(
+        &self,
+        connection: &StreamingConnection,
+        reference_id: &str,
+        client_key: &str,
+        account_key: Option<&str>,
+    ) -> Result<
+        (
+            portfolio::accounts::list::Response,
+            UnboundedReceiver<SubscriptionUpdate>,
+        ),
+        SaxoError,
+    > {
+        self.create_subscription(
+            connection,
+            portfolio::accounts::subscription(reference_id, client_key, account_key),
+        )
+        .await
+    }
+
+    /// Subscribes to the balance of a client, or of a single account if `account_key` is given.
+    pub async fn subscribe_port_balances(
+        &self,
+        connection: &StreamingConnection,
+        reference_id: &str,
+        client_key: &str,
+        account_key: Option<&str>,
+    ) -> Result<
+        (
+            portfolio::balances::Response,
+            UnboundedReceiver<SubscriptionUpdate>,
+        ),
+        SaxoError,
+    > {
+        self.create_subscription(
+            connection,
+            portfolio::balances::subscription(reference_id, client_key, account_key),
+        )
+        .await
+    }
+
+    /// Subscribes to the positions of a client, or of a single account if `account_key` is given.
+    pub async fn subscribe_port_positions(
+        &self,
+        connection: &StreamingConnection,
+        reference_id: &str,
+        client_key: &str,
+        account_key: Option<&str>,
+        field_groups: &FieldGroups<portfolio::positions::FieldGroup>,
+    ) -> Result<
+        (
+            portfolio::positions::list::Response,
+            UnboundedReceiver<SubscriptionUpdate>,
+        ),
+        SaxoError,
+    > {
+        self.create_subscription(
+            connection,
+            portfolio::positions::subscription(reference_id, client_key, account_key, field_groups),
+        )
+        .await
+    }
+
+    /// Subscribes to the working orders of a client, or of a single account if `account_key` is
+    /// given.
+    pub async fn subscribe_port_orders(
+        &self,
+        connection: &StreamingConnection,
+        reference_id: &str,
+        client_key: &str,
+        account_key: Option<&str>,
+        field_groups: &FieldGroups<portfolio::orders::FieldGroup>,
+    ) -> Result<
+        (
+            portfolio::orders::list::Response,
+            UnboundedReceiver<SubscriptionUpdate>,
+        ),
+        SaxoError,
+    > {
+        self.create_subscription(
+            connection,
+            portfolio::orders::subscription(reference_id, client_key, account_key, field_groups),
+        )
+        .await
+    }
+
+    /// Subscribes to the net positions of a client, or of a single account if `account_key` is given.
+    pub async fn subscribe_port_net_positions(
+        &self,
+        connection: &StreamingConnection,
+        reference_id: &str,
+        client_key: &str,
+        account_key: Option<&str>,
+        field_groups: &FieldGroups<portfolio::netpositions::FieldGroup>,
+    ) -> Result<
+        (
+            portfolio::netpositions::list::Response,
+            UnboundedReceiver<SubscriptionUpdate>,
+        ),
+        SaxoError,
+    > {
+        self.create_subscription(
+            connection,
+            portfolio::netpositions::subscription(
+                reference_id,
+                client_key,
+                account_key,
+                field_groups,
+            ),
+        )
+        .await
+    }
+
+    /// Subscribes to the tradable quote of an instrument, sent at most every `refresh_rate`
+    /// milliseconds if given. The subscription is deleted when the stream is dropped.
+    pub async fn subscribe_trade_prices(
+        &self,
+        connection: &StreamingConnection,
+        reference_id: &str,
+        uic: i32,
+        asset_type: &AssetType,
+        refresh_rate: Option<u32>,
+    ) -> Result<QuoteStream, SaxoError> {
+        let field_groups = [trading::infoprices::FieldGroup::Quote]
+            .into_iter()
+            .collect();
+        let mut subscription =
+            trading::prices::subscription(reference_id, uic, asset_type, &field_groups);
+        subscription.refresh_rate = refresh_rate;
+
+        let (snapshot, receiver) = self.create_subscription(connection, subscription).await?;
+        Ok(QuoteStream::single(
+            snapshot,
+            receiver,
+            Some(connection.guard(reference_id)),
+        ))
+    }
+
+    /// Subscribes to the quotes of several instruments of the same asset type, sent at most
+    /// every `refresh_rate` milliseconds if given. The subscription is deleted when the stream
+    /// is dropped.
+    pub async fn subscribe_trade_info_prices(
+        &self,
+        connection: &StreamingConnection,
+        reference_id: &str,
+        uics: &[i32],
+        asset_type: &AssetType,
+        refresh_rate: Option<u32>,
+    ) -> Result<QuoteStream, SaxoError> {
+        let field_groups = [trading::infoprices::FieldGroup::Quote]
+            .into_iter()
+            .collect();
+        let mut subscription =
+            trading::infoprices::subscription(reference_id, uics, asset_type, &field_groups);
+        subscription.refresh_rate = refresh_rate;
+
+        let (snapshot, receiver) = self.create_subscription(connection, subscription).await?;
+        Ok(QuoteStream::list(
+            snapshot,
+            receiver,
+            Some(connection.guard(reference_id)),
+        ))
+    }
+}
+
+#[async_trait]
+impl<S: HttpSend + Send + Sync> Subscriber for SaxoClient<S> {
+    async fn subscribe(
+        &self,
+        context_id: &str,
+        subscription: &Subscription,
+    ) -> Result<SubscriptionResponse, SaxoError> {
+        self.post(subscription.endpoint, &subscription.request(context_id))
+            .await
+    }
+
+    async fn unsubscribe(
+        &self,
+        context_id: &str,
+        subscription: &Subscription,
+    ) -> Result<(), SaxoError> {
+        self.delete(&format!(
+            "{}/{}/{}",
+            subscription.endpoint.trim_end_matches('/'),
+            context_id,
+            subscription.reference_id
+        ))
+        .await
+    }
+
+    async fn authorize(&self, context_id: &str) -> Result<(), SaxoError> {
+        // The authorization is a plain request to the host of the WebSocket
+        let mut url = self.streaming_url.join("streamingws/authorize")?;
+        let scheme = if url.scheme() == "ws" {
+            "http"
+        } else {
+            "https"
+        };
+        let _ = url.set_scheme(scheme);
+        url.query_pairs_mut().append_pair("contextid", context_id);
+        let response = self.send(|| self.client.put(url.clone())).await?;
+
+        Self::check_status(response).await.map(|_| ())
+    }
+}
+
+/// Builds a [`SaxoClient`], e.g. pointing at a mock server or going through a proxy.
+pub struct SaxoClientBuilder<S: HttpSend = Sender> {
+    sender: S,
+    token: Arc<dyn TokenProvider>,
+    base_url: Url,
+    streaming_url: Url,
+    user_agent: String,
+    precheck_orders: bool,
+}
+
+impl SaxoClientBuilder<Sender> {
+    /// Creates a builder for the simulation environment.
+    #[must_use]
+    pub fn new(token: Arc<dyn TokenProvider>) -> Self {
+        SaxoClientBuilder {
+            sender: Sender,
+            token,
+            base_url: parse_static(Env::Sim.gateway_url()),
+            streaming_url: parse_static(Env::Sim.streaming_url()),
+            user_agent: USER_AGENT.to_owned(),
+            precheck_orders: false,
+        }
+    }
+}
+
+impl<S: HttpSend> SaxoClientBuilder<S> {
+    /// Uses the gateway and streaming urls of `env`.
+    #[must_use]
+    pub fn env(self, env: Env) -> Self {
+        self.base_url(parse_static(env.gateway_url()))
+            .streaming_url(parse_static(env.streaming_url()))
+    }
+
+    /// Sets the url the paths of the REST end points are joined to, e.g.
+    /// `https://gateway.saxobank.com/sim/openapi/`.
+    #[must_use]
+    pub fn base_url(mut self, url: Url) -> Self {
+        self.base_url = with_trailing_slash(url);
+        self
+    }
+
+    /// Sets the url the streaming WebSocket path is joined to, e.g.
+    /// `wss://streaming.saxobank.com/sim/openapi/`.
+    #[must_use]
+    pub fn streaming_url(mut self, url: Url) -> Self {
+        self.streaming_url = with_trailing_slash(url);
+        self
+    }
+
+    #[must_use]
+    pub fn user_agent(mut self, user_agent: &str) -> Self {
+        user_agent.clone_into(&mut self.user_agent);
+        self
+    }
+
+    /// Prechecks orders before placing them, so violations fail without sending the order.
+    /// Disabled by default.
+    #[must_use]
+    pub fn precheck_orders(mut self, precheck_orders: bool) -> Self {
+        self.precheck_orders = precheck_orders;
+        self
+    }
+
+    /// Sends the requests with `sender` instead of `reqwest`.
+    pub fn sender<T: HttpSend>(self, sender: T) -> SaxoClientBuilder<T> {
+        SaxoClientBuilder {
+            sender,
+            token: self.token,
+            base_url: self.base_url,
+            streaming_url: self.streaming_url,
+            user_agent: self.user_agent,
+            precheck_orders: self.precheck_orders,
+        }
+    }
+
+    pub fn build(self) -> Result<SaxoClient<S>, SaxoClientError> {
+        Ok(SaxoClient {
+            client: reqwest::ClientBuilder::new()
+                .default_headers(Self::create_headers())
+                .user_agent(self.user_agent)
+                .build()?,
+            sender: self.sender,
+            base_url: self.base_url,
+            streaming_url: self.streaming_url,
+            token: self.token,
+            precheck_orders: self.precheck_orders,
+        })
+    }
+
+    fn create_headers() -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert("Accept", HeaderValue::from_static("*/*"));
+        headers.insert("Content-Type", HeaderValue::from_static("application/json"));
+        headers
+    }
+}
+
+fn parse_static(url: &'static str) -> Url {
+    Url::parse(url).expect("valid url")
+}
+
+/// Urls are joined relative to their last segment, so the base must end with a slash to keep it.
+fn with_trailing_slash(mut url: Url) -> Url {
+    if !url.path().ends_with('/') {
+        let path = format!("{}/", url.path());
+        url.set_path(&path);
+    }
+    url
+}
+
 pub struct NextHandle<'a, S: HttpSend, T: SaxoResponseOData> {
     client: &'a SaxoClient<S>,
     resp: T,
 }
 
 impl<'a, S: HttpSend, T: SaxoResponseOData> NextHandle<'a, S, T> {
+    /// The current page.
+    #[must_use]
+    pub fn response(&self) -> &T {
+        &self.resp
+    }
+
+    #[must_use]
+    pub fn into_response(self) -> T {
+        self.resp
+    }
+
+    /// Whether there is a page after the current one.
+    #[must_use]
+    pub fn has_next(&self) -> bool {
+        self.resp.next().is_some()
+    }
+
     pub async fn next(
         self,
     ) -> Result<
@@ -208,7 +1170,7 @@ impl<'a, S: HttpSend, T: SaxoResponseOData> NextHandle<'a, S, T> {
     }
 }
 
-impl<'a, S: HttpSend, T: SaxoResponseOData> fmt::Debug for NextHandle<'a, S, T> {
+impl<S: HttpSend, T: SaxoResponseOData> fmt::Debug for NextHandle<'_, S, T> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         fmt::Debug::fmt(&self.resp, f)
     }
@@ -218,9 +1180,18 @@ impl<'a, S: HttpSend, T: SaxoResponseOData> fmt::Debug for NextHandle<'a, S, T>
 mod tests {
     use super::*;
 
+    use crate::auth::provider::MockTokenProvider;
     use crate::{error::ErrorCode, saxo_request_odata, saxo_response_odata};
     use serde_json::json;
 
+    fn authorization(request: &reqwest::RequestBuilder) -> String {
+        let request = request.try_clone().unwrap().build().unwrap();
+        request.headers()["Authorization"]
+            .to_str()
+            .unwrap()
+            .to_owned()
+    }
+
     #[tokio::test]
     async fn test_parse_ok() {
         let response =
@@ -272,7 +1243,7 @@ mod tests {
             assert_eq!(c.error_code(), &ErrorCode::InvalidRequest);
             assert_eq!(c.message(), "Invalid request message");
         } else {
-            assert!(false);
+            panic!("expected SaxoError::BadRequest");
         }
     }
 
@@ -306,6 +1277,538 @@ mod tests {
         assert_eq!(resp.language.unwrap(), "C++");
     }
 
+    #[tokio::test]
+    async fn test_get_port_accounts() {
+        let mut mock_sender = MockHttpSend::new();
+        mock_sender
+            .expect_send()
+            .once()
+            .withf(|request| {
+                let request = request.try_clone().unwrap().build().unwrap();
+                request.url().path() == "/sim/openapi/port/v1/accounts/"
+                    && request.url().query() == Some("ClientKey=foo%7Cbar")
+            })
+            .returning(|_| {
+                Ok(reqwest::Response::from(
+                    http::Response::builder()
+                        .status(200)
+                        .body(json!({ "Data": [{ "AccountKey": "baz" }] }).to_string())
+                        .unwrap(),
+                ))
+            });
+
+        let client = SaxoClient::sim_with_sender(mock_sender, "").unwrap();
+        let resp = client.get_port_accounts("foo|bar").await.unwrap();
+
+        assert_eq!(resp.data[0].account_key.as_deref(), Some("baz"));
+    }
+
+    #[tokio::test]
+    async fn test_get_port_positions() {
+        let mut mock_sender = MockHttpSend::new();
+        mock_sender
+            .expect_send()
+            .once()
+            .withf(|request| {
+                let request = request.try_clone().unwrap().build().unwrap();
+                request.url().query()
+                    == Some("ClientKey=foo&FieldGroups=PositionBase%2CPositionView&AccountKey=bar")
+            })
+            .returning(|_| {
+                Ok(reqwest::Response::from(
+                    http::Response::builder()
+                        .status(200)
+                        .body(json!({ "Data": [{ "PositionId": "baz" }] }).to_string())
+                        .unwrap(),
+                ))
+            });
+
+        let client = SaxoClient::sim_with_sender(mock_sender, "").unwrap();
+        let field_groups = FieldGroups::new()
+            .with(portfolio::positions::FieldGroup::PositionView)
+            .with(portfolio::positions::FieldGroup::PositionBase);
+        let resp = client
+            .get_port_positions("foo", Some("bar"), &field_groups)
+            .await
+            .unwrap();
+
+        assert_eq!(resp.data[0].position_id.as_deref(), Some("baz"));
+    }
+
+    #[tokio::test]
+    async fn test_place_order() {
+        let mut mock_sender = MockHttpSend::new();
+        mock_sender
+            .expect_send()
+            .once()
+            .withf(|request| {
+                let request = request.try_clone().unwrap().build().unwrap();
+                let body: serde_json::Value =
+                    serde_json::from_slice(request.body().unwrap().as_bytes().unwrap()).unwrap();
+                request.method() == reqwest::Method::POST
+                    && request.url().path() == "/sim/openapi/trade/v2/orders"
+                    && body["OrderType"] == "Limit"
+                    && body["OrderPrice"] == 1.05
+            })
+            .returning(|_| {
+                Ok(reqwest::Response::from(
+                    http::Response::builder()
+                        .status(200)
+                        .body(json!({ "OrderId": "123" }).to_string())
+                        .unwrap(),
+                ))
+            });
+
+        let client = SaxoClient::sim_with_sender(mock_sender, "").unwrap();
+        let order = trading::orders::Order::limit(
+            "account",
+            21,
+            AssetType::FxSpot,
+            portfolio::orders::BuySell::Buy,
+            1000.0,
+            1.05,
+        );
+
+        assert_eq!(client.place_order(&order).await.unwrap().order_id, "123");
+    }
+
+    #[tokio::test]
+    async fn test_place_order_precheck_failed() {
+        let mut mock_sender = MockHttpSend::new();
+        mock_sender
+            .expect_send()
+            .once()
+            .withf(|request| {
+                let request = request.try_clone().unwrap().build().unwrap();
+                request.url().path() == "/sim/openapi/trade/v2/orders/precheck"
+            })
+            .returning(|_| {
+                Ok(reqwest::Response::from(
+                    http::Response::builder()
+                        .status(200)
+                        .body(
+                            json!({
+                                "ErrorInfo": {
+                                    "ErrorCode": "InsufficientMargin",
+                                    "Message": "Order placement would exceed margin"
+                                },
+                                "PreCheckResult": "Error"
+                            })
+                            .to_string(),
+                        )
+                        .unwrap(),
+                ))
+            });
+
+        let client = SaxoClient::builder(Arc::new(StaticToken::new("")))
+            .precheck_orders(true)
+            .sender(mock_sender)
+            .build()
+            .unwrap();
+        let order = trading::orders::Order::market(
+            "account",
+            21,
+            AssetType::FxSpot,
+            portfolio::orders::BuySell::Buy,
+            1000.0,
+        );
+
+        match client.place_order(&order).await {
+            Err(SaxoError::PrecheckFailed(violation)) => {
+                assert_eq!(violation.error_code, "InsufficientMargin");
+            }
+            _ => panic!("expected SaxoError::PrecheckFailed"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_place_multi_leg_order() {
+        let mut mock_sender = MockHttpSend::new();
+        mock_sender
+            .expect_send()
+            .times(2)
+            .withf(|request| {
+                let request = request.try_clone().unwrap().build().unwrap();
+                let body: serde_json::Value =
+                    serde_json::from_slice(request.body().unwrap().as_bytes().unwrap()).unwrap();
+                request.method() == reqwest::Method::POST && body["Legs"][1]["Amount"] == 2.0
+            })
+            .returning(|request| {
+                let request = request.build().unwrap();
+                let body = if request.url().path().ends_with("/precheck") {
+                    json!({ "PreCheckResult": "Ok" })
+                } else {
+                    json!({
+                        "MultiLegOrderId": "5001",
+                        "Orders": [{ "OrderId": "1" }, { "OrderId": "2" }]
+                    })
+                };
+                Ok(reqwest::Response::from(
+                    http::Response::builder()
+                        .status(200)
+                        .body(body.to_string())
+                        .unwrap(),
+                ))
+            });
+
+        let client = SaxoClient::builder(Arc::new(StaticToken::new("")))
+            .precheck_orders(true)
+            .sender(mock_sender)
+            .build()
+            .unwrap();
+        let contract = trading::multileg::OptionContract {
+            option_root_id: 19,
+            strike: 180.0,
+            expiry: chrono::NaiveDate::from_ymd_opt(2023, 6, 16).unwrap(),
+            put_call: portfolio::orders::PutCall::Put,
+        };
+        let order = trading::multileg::MultiLegOrder::market("account", 1.0)
+            .leg(trading::multileg::OptionLeg::new(
+                1,
+                contract,
+                portfolio::orders::BuySell::Buy,
+                1,
+            ))
+            .leg(trading::multileg::OptionLeg::new(
+                2,
+                trading::multileg::OptionContract {
+                    strike: 170.0,
+                    ..contract
+                },
+                portfolio::orders::BuySell::Sell,
+                2,
+            ))
+            .build()
+            .unwrap();
+
+        let order_id = client.place_multi_leg_order(&order).await.unwrap();
+        assert_eq!(order_id.multi_leg_order_id, "5001");
+        assert_eq!(order_id.orders.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_get_trade_info_prices() {
+        let mut mock_sender = MockHttpSend::new();
+        mock_sender
+            .expect_send()
+            .once()
+            .withf(|request| {
+                let request = request.try_clone().unwrap().build().unwrap();
+                request.url().path() == "/sim/openapi/trade/v1/infoprices/list"
+                    && request.url().query()
+                        == Some("AssetType=Stock&FieldGroups=PriceInfo%2CQuote&Uics=211%2C212")
+            })
+            .returning(|_| {
+                Ok(reqwest::Response::from(
+                    http::Response::builder()
+                        .status(200)
+                        .body(
+                            json!({
+                                "Data": [
+                                    { "AssetType": "Stock", "Quote": { "Mid": 180.5 }, "Uic": 211 },
+                                    { "AssetType": "Stock", "Quote": { "Mid": 42.1 }, "Uic": 212 }
+                                ]
+                            })
+                            .to_string(),
+                        )
+                        .unwrap(),
+                ))
+            });
+
+        let client = SaxoClient::sim_with_sender(mock_sender, "").unwrap();
+        let field_groups = [
+            trading::infoprices::FieldGroup::Quote,
+            trading::infoprices::FieldGroup::PriceInfo,
+        ]
+        .into_iter()
+        .collect();
+        let response = client
+            .get_trade_info_prices(&[211, 212], &AssetType::Stock, &field_groups)
+            .await
+            .unwrap();
+
+        assert_eq!(response.data.len(), 2);
+        assert_eq!(response.data[1].uic, Some(212));
+    }
+
+    #[tokio::test]
+    async fn test_get_ref_instrument_details() {
+        let mut mock_sender = MockHttpSend::new();
+        mock_sender
+            .expect_send()
+            .once()
+            .withf(|request| {
+                let request = request.try_clone().unwrap().build().unwrap();
+                request.url().path() == "/sim/openapi/ref/v1/instruments/details/211/Stock"
+                    && request.url().query() == Some("FieldGroups=OrderSetting%2CTradingSessions")
+            })
+            .returning(|_| {
+                Ok(reqwest::Response::from(
+                    http::Response::builder()
+                        .status(200)
+                        .body(json!({ "AssetType": "Stock", "Uic": 211 }).to_string())
+                        .unwrap(),
+                ))
+            });
+
+        let client = SaxoClient::sim_with_sender(mock_sender, "").unwrap();
+        let details = client
+            .get_ref_instrument_details(
+                211,
+                &AssetType::Stock,
+                &reference_data::instruments::details::FieldGroup::defaults(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(details.uic, Some(211));
+    }
+
+    #[tokio::test]
+    async fn test_get_ref_data_cache() {
+        let mut mock_sender = MockHttpSend::new();
+        mock_sender.expect_send().times(5).returning(|request| {
+            let request = request.build().unwrap();
+            let data = match request.url().path() {
+                "/sim/openapi/ref/v1/currencies/" => {
+                    json!([{ "CurrencyCode": "EUR", "Decimals": 2 }])
+                }
+                "/sim/openapi/ref/v1/timezones/" => {
+                    json!([{ "TimeZoneId": 2, "ZoneName": "Europe/Copenhagen" }])
+                }
+                _ => json!([]),
+            };
+            Ok(reqwest::Response::from(
+                http::Response::builder()
+                    .status(200)
+                    .body(json!({ "Data": data }).to_string())
+                    .unwrap(),
+            ))
+        });
+
+        let client = SaxoClient::sim_with_sender(mock_sender, "").unwrap();
+        let cache = client.get_ref_data_cache().await.unwrap();
+
+        assert_eq!(cache.currency_decimals("EUR"), Some(2));
+        assert_eq!(
+            cache.time_zone(2).unwrap().zone_name.as_deref(),
+            Some("Europe/Copenhagen")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_subscribe_port_accounts() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("ws://{}/connect", listener.local_addr().unwrap());
+        let _server = tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let mut socket = tokio_tungstenite::accept_async(stream).await.unwrap();
+            while let Some(Ok(_)) = futures_util::StreamExt::next(&mut socket).await {}
+        });
+
+        let mut mock_sender = MockHttpSend::new();
+        mock_sender
+            .expect_send()
+            .once()
+            .withf(|request| {
+                let request = request.try_clone().unwrap().build().unwrap();
+                let body = serde_json::from_slice::<serde_json::Value>(
+                    request.body().unwrap().as_bytes().unwrap(),
+                )
+                .unwrap();
+                request.method() == reqwest::Method::POST
+                    && request.url().path() == "/sim/openapi/port/v1/accounts/subscriptions"
+                    && body
+                        == json!({
+                            "ContextId": "ctx",
+                            "ReferenceId": "accounts",
+                            "Arguments": { "ClientKey": "client", "AccountKey": "account" }
+                        })
+            })
+            .returning(|_| {
+                Ok(reqwest::Response::from(
+                    http::Response::builder()
+                        .status(201)
+                        .body(
+                            json!({
+                                "ReferenceId": "accounts",
+                                "Snapshot": { "Data": [{ "AccountKey": "account" }] }
+                            })
+                            .to_string(),
+                        )
+                        .unwrap(),
+                ))
+            });
+
+        let client = Arc::new(SaxoClient::sim_with_sender(mock_sender, "").unwrap());
+        let connection = StreamingConnection::connect(
+            &url,
+            Arc::new(StaticToken::new("token")),
+            "ctx",
+            client.clone(),
+        )
+        .await
+        .unwrap();
+        let (snapshot, _receiver) = client
+            .subscribe_port_accounts(&connection, "accounts", "client", Some("account"))
+            .await
+            .unwrap();
+
+        assert_eq!(snapshot.data[0].account_key.as_deref(), Some("account"));
+    }
+
+    #[tokio::test]
+    async fn test_authorize_streaming() {
+        let mut mock_sender = MockHttpSend::new();
+        mock_sender
+            .expect_send()
+            .once()
+            .withf(|request| {
+                let request = request.try_clone().unwrap().build().unwrap();
+                request.method() == reqwest::Method::PUT
+                    && request.url().as_str()
+                        == "https://streaming.saxobank.com/sim/openapi/streamingws/authorize?contextid=ctx"
+            })
+            .returning(|_| {
+                Ok(reqwest::Response::from(
+                    http::Response::builder().status(202).body("").unwrap(),
+                ))
+            });
+
+        let client = SaxoClient::sim_with_sender(mock_sender, "").unwrap();
+
+        assert!(client.authorize("ctx").await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_cancel_orders() {
+        let mut mock_sender = MockHttpSend::new();
+        mock_sender
+            .expect_send()
+            .once()
+            .withf(|request| {
+                let request = request.try_clone().unwrap().build().unwrap();
+                request.method() == reqwest::Method::DELETE
+                    && request.url().path() == "/sim/openapi/trade/v2/orders/1,2"
+                    && request.url().query() == Some("AccountKey=foo%3D%3D")
+            })
+            .returning(|_| {
+                Ok(reqwest::Response::from(
+                    http::Response::builder()
+                        .status(200)
+                        .body(
+                            json!({ "Orders": [{ "OrderId": "1" }, { "OrderId": "2" }] })
+                                .to_string(),
+                        )
+                        .unwrap(),
+                ))
+            });
+
+        let client = SaxoClient::sim_with_sender(mock_sender, "").unwrap();
+        let resp = client.cancel_orders("foo==", &["1", "2"]).await.unwrap();
+
+        assert_eq!(resp.orders.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_refresh_unauthorized() {
+        let mut token = MockTokenProvider::new();
+        token
+            .expect_access_token()
+            .once()
+            .returning(|| Ok("old".to_owned()));
+        token
+            .expect_refresh()
+            .once()
+            .returning(|| Ok("new".to_owned()));
+
+        let mut mock_sender = MockHttpSend::new();
+        let mut sequence = mockall::Sequence::new();
+        mock_sender
+            .expect_send()
+            .once()
+            .in_sequence(&mut sequence)
+            .withf(|request| authorization(request) == "BEARER old")
+            .returning(|_| {
+                Ok(reqwest::Response::from(
+                    http::Response::builder().status(401).body("").unwrap(),
+                ))
+            });
+        mock_sender
+            .expect_send()
+            .once()
+            .in_sequence(&mut sequence)
+            .withf(|request| authorization(request) == "BEARER new")
+            .returning(|_| {
+                Ok(reqwest::Response::from(
+                    http::Response::builder()
+                        .status(200)
+                        .body(json!({ "Name": "Foo" }).to_string())
+                        .unwrap(),
+                ))
+            });
+
+        let client = SaxoClient::with_sender(mock_sender, Arc::new(token), Env::Sim).unwrap();
+        let resp = client.get_port_user_info().await.unwrap();
+
+        assert_eq!(resp.name.unwrap(), "Foo");
+    }
+
+    #[tokio::test]
+    async fn test_static_token_unauthorized() {
+        let mut mock_sender = MockHttpSend::new();
+        mock_sender.expect_send().once().returning(|_| {
+            Ok(reqwest::Response::from(
+                http::Response::builder().status(401).body("").unwrap(),
+            ))
+        });
+
+        let client = SaxoClient::sim_with_sender(mock_sender, "").unwrap();
+
+        assert!(matches!(
+            client.get_port_user_info().await,
+            Err(SaxoError::Unauthorized)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_builder_base_url() {
+        let mut mock_sender = MockHttpSend::new();
+        mock_sender
+            .expect_send()
+            .once()
+            .withf(|request| {
+                let request = request.try_clone().unwrap().build().unwrap();
+                request.url().as_str() == "http://localhost:8080/proxy/port/v1/users/me"
+            })
+            .returning(|_| {
+                Ok(reqwest::Response::from(
+                    http::Response::builder().status(200).body("{}").unwrap(),
+                ))
+            });
+
+        let client = SaxoClient::builder(Arc::new(StaticToken::new("")))
+            .base_url(Url::parse("http://localhost:8080/proxy").unwrap())
+            .user_agent("foo/1.0")
+            .sender(mock_sender)
+            .build()
+            .unwrap();
+
+        assert_eq!(client.base_url().as_str(), "http://localhost:8080/proxy/");
+        assert!(client.get_port_user_info().await.is_ok());
+    }
+
+    #[test]
+    fn test_builder_env() {
+        let client = SaxoClient::builder(Arc::new(StaticToken::new("")))
+            .env(Env::Live)
+            .build()
+            .unwrap();
+
+        assert_eq!(client.base_url().as_str(), Env::Live.gateway_url());
+        assert_eq!(client.streaming_url.as_str(), Env::Live.streaming_url());
+    }
+
     #[tokio::test]
     async fn test_get_odata_next() {
         saxo_request_odata!("foo/bar/");
@@ -352,6 +1855,75 @@ mod tests {
 
     #[tokio::test]
     async fn test_next_handle() {
-        // TODO
+        let mut mock_sender = MockHttpSend::new();
+        let mut sequence = mockall::Sequence::new();
+        mock_sender
+            .expect_send()
+            .once()
+            .in_sequence(&mut sequence)
+            .withf(|request| {
+                let request = request.try_clone().unwrap().build().unwrap();
+                request.url().query() == Some("ClientKey=foo&FieldGroups=ClosedPosition&%24top=1")
+            })
+            .returning(|_| {
+                Ok(reqwest::Response::from(
+                    http::Response::builder()
+                        .status(200)
+                        .body(
+                            json!({
+                                "__next": "/openapi/port/v1/closedpositions/?ClientKey=foo&$top=1&$skip=1",
+                                "Data": [{ "ClosedPositionUniqueId": "1" }]
+                            })
+                            .to_string(),
+                        )
+                        .unwrap(),
+                ))
+            });
+        mock_sender
+            .expect_send()
+            .once()
+            .in_sequence(&mut sequence)
+            .withf(|request| {
+                let request = request.try_clone().unwrap().build().unwrap();
+                request.url().path() == "/sim/openapi/port/v1/closedpositions/"
+                    && request.url().query() == Some("ClientKey=foo&%24top=1&%24skip=1")
+            })
+            .returning(|_| {
+                Ok(reqwest::Response::from(
+                    http::Response::builder()
+                        .status(200)
+                        .body(json!({ "Data": [{ "ClosedPositionUniqueId": "2" }] }).to_string())
+                        .unwrap(),
+                ))
+            });
+
+        let client = SaxoClient::sim_with_sender(mock_sender, "").unwrap();
+        let field_groups =
+            FieldGroups::new().with(portfolio::closedpositions::FieldGroup::ClosedPosition);
+        let params = ODataParams {
+            top: Some(1),
+            skip: None,
+        };
+
+        let mut ids = Vec::new();
+        let mut handle = client
+            .get_port_closed_positions("foo", None, &field_groups, &params)
+            .await
+            .unwrap();
+        loop {
+            ids.extend(
+                handle
+                    .response()
+                    .data
+                    .iter()
+                    .filter_map(|position| position.closed_position_unique_id.clone()),
+            );
+            if !handle.has_next() {
+                break;
+            }
+            handle = handle.next().await.unwrap();
+        }
+
+        assert_eq!(ids, vec!["1", "2"]);
     }
 }
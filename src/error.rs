@@ -12,7 +12,7 @@ pub struct SaxoClientError(#[from] reqwest::Error);
 #[derive(thiserror::Error, Debug)]
 pub enum SaxoError {
     #[error("HTTP error")]
-    HTTPError(#[from] Box<dyn StdError>),
+    HTTPError(#[from] Box<dyn StdError + Send + Sync>),
 
     #[error("unauthorized. Did you forget a valid Open API token?")]
     Unauthorized,
@@ -20,8 +20,73 @@ pub enum SaxoError {
     #[error("Bad request")]
     BadRequest(SaxoBadRequest),
 
+    #[error("Authentication failed: {0}")]
+    Auth(AuthError),
+
+    #[error("Invalid certificate or private key")]
+    Certificate(Box<dyn StdError + Send + Sync>),
+
+    #[error("Order rejected by precheck: {0}")]
+    PrecheckFailed(ErrorInfo),
+
+    #[error("Invalid url")]
+    Url(#[from] url::ParseError),
+
+    #[error("Failed to decode JSON: {0}")]
+    Json(#[from] serde_json::Error),
+
     #[error("Ill-formed OData ")]
     IllFormedOData,
+
+    #[error("Streaming error")]
+    Streaming(Box<tokio_tungstenite::tungstenite::Error>),
+
+    #[error("Streaming connection was closed by the server")]
+    StreamingDisconnected,
+
+    #[error("Ill-formed streaming frame: {0}")]
+    Frame(FrameError),
+}
+
+/// Error parsing a .NET `TimeSpan` string.
+#[derive(thiserror::Error, Debug, PartialEq, Eq)]
+#[error("Invalid time span {0:?}")]
+pub struct TimeSpanError(pub String);
+
+/// Error decoding a binary frame received on the streaming connection.
+#[derive(thiserror::Error, Debug, PartialEq, Eq)]
+pub enum FrameError {
+    #[error("Truncated message starting at byte {0}")]
+    Truncated(usize),
+
+    #[error("Reference id is not valid UTF-8")]
+    InvalidReferenceId,
+
+    #[error("Unknown payload format {0}")]
+    UnknownPayloadFormat(u8),
+}
+
+/// Pre-trade violation which would make the placement fail.
+#[derive(Deserialize, Debug, Default, Clone, PartialEq, Eq)]
+#[serde(rename_all = "PascalCase")]
+pub struct ErrorInfo {
+    pub error_code: String,
+    pub message: String,
+}
+
+impl ErrorInfo {
+    pub(crate) fn unknown() -> Self {
+        ErrorInfo {
+            error_code: "Unknown".to_owned(),
+            message: "Precheck failed without error info".to_owned(),
+        }
+    }
+}
+
+impl fmt::Display for ErrorInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.error_code, self.message)
+    }
 }
 
 impl From<reqwest::Error> for SaxoError {
@@ -30,6 +95,12 @@ impl From<reqwest::Error> for SaxoError {
     }
 }
 
+impl From<tokio_tungstenite::tungstenite::Error> for SaxoError {
+    fn from(err: tokio_tungstenite::tungstenite::Error) -> Self {
+        SaxoError::Streaming(Box::new(err))
+    }
+}
+
 type ModelStateType = HashMap<String, Vec<String>>;
 
 #[allow(non_snake_case)]
@@ -57,6 +128,22 @@ impl fmt::Display for SaxoBadRequest {
     }
 }
 
+/// Error returned by the token end point of the Saxo SSO.
+#[derive(Debug, Deserialize, Default, PartialEq, Eq)]
+pub struct AuthError {
+    pub error: String,
+    pub error_description: Option<String>,
+}
+
+impl fmt::Display for AuthError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match &self.error_description {
+            Some(description) => write!(f, "{} ({description})", self.error),
+            None => write!(f, "{}", self.error),
+        }
+    }
+}
+
 /// Generic and domain specific error codes
 #[derive(Debug, PartialEq, Default)]
 pub enum ErrorCode {
@@ -74,7 +161,7 @@ pub enum ErrorCode {
     InvalidQueryParameters,
     /// Error code returned when model state is invalid.
     InvalidModelState,
-    /// Error code returned when type-conversion failed (TypeConverter's and ModelBinder's).
+    /// Error code returned when type-conversion failed (`TypeConverter`'s and `ModelBinder`'s).
     TypeConversionError,
     /// Error code returned when more than the maximum allowed number of subscriptions for a specified type,
     /// is exceeded.
@@ -222,7 +309,7 @@ mod tests {
     }
 
     /// Test that the format sampled in
-    /// https://www.developer.saxo/openapi/learn/openapi-request-response
+    /// <https://www.developer.saxo/openapi/learn/openapi-request-response>
     /// Indeed can be deserialized, with our struct.
     #[test]
     fn test_serde_model_state() {
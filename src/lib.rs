@@ -2,22 +2,34 @@
 #![allow(clippy::missing_errors_doc)]
 #![allow(clippy::module_name_repetitions)]
 
+pub mod auth;
+pub mod cache;
+pub mod calendar;
 pub mod client;
 pub mod error;
 pub mod messages;
+pub mod streaming;
 
 use std::fmt;
 use std::fmt::Formatter;
 
 pub enum EndPointArgument {
-    Id(&'static str),
+    Id(String),
     OData(ODataParams),
+    /// Id followed by query parameters, e.g. `?ClientKey=foo`
+    Query(String, Vec<(String, String)>),
 }
 
 impl fmt::Display for EndPointArgument {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
         match self {
             EndPointArgument::Id(id) => write!(f, "{id}"),
+            EndPointArgument::Query(id, params) => {
+                let query = url::form_urlencoded::Serializer::new(String::new())
+                    .extend_pairs(params)
+                    .finish();
+                write!(f, "{id}?{query}")
+            }
             EndPointArgument::OData(odata) => {
                 let mid = Self::get_mid(odata);
                 Self::write_end_point(f, odata, mid)
@@ -87,11 +99,43 @@ macro_rules! saxo_request {
 
         impl Request {
             #[must_use]
-            pub fn new(id: &'static str) -> Self {
+            pub fn new(id: &str) -> Self {
                 Request {
-                    argument: EndPointArgument::Id(id),
+                    argument: EndPointArgument::Id(id.to_owned()),
                 }
             }
+
+            #[must_use]
+            pub fn with_query(id: &str, params: &[(&str, &str)]) -> Self {
+                Request {
+                    argument: EndPointArgument::Query(
+                        id.to_owned(),
+                        params
+                            .iter()
+                            .map(|(key, value)| ((*key).to_owned(), (*value).to_owned()))
+                            .collect(),
+                    ),
+                }
+            }
+
+            /// Request of the `__next` link of a paged response, which repeats the id and query
+            /// parameters of the original request.
+            #[must_use]
+            pub fn from_next(next: &str) -> Option<Self> {
+                let uri = next.parse::<http::Uri>().ok()?;
+                let endpoint = $str.trim_end_matches('/');
+                let (_, id) = uri.path().split_once(endpoint)?;
+                let params = url::form_urlencoded::parse(uri.query()?.as_bytes())
+                    .into_owned()
+                    .collect();
+
+                Some(Request {
+                    argument: EndPointArgument::Query(
+                        id.trim_start_matches('/').to_owned(),
+                        params,
+                    ),
+                })
+            }
         }
 
         impl SaxoRequest for Request {
@@ -208,6 +252,42 @@ macro_rules! saxo_response_odata {
     };
 }
 
+/// Defines a Response listing items of the given type, e.g. `port/v1/accounts/me`.
+#[macro_export]
+macro_rules! saxo_response_list {
+    (struct $name:ident { $data:ty }) => {
+        #[derive(serde::Deserialize, Debug, Default, PartialEq)]
+        pub struct $name {
+            #[serde(rename = "__count")]
+            pub count: Option<i32>,
+            #[serde(rename = "__next")]
+            pub next: Option<String>,
+            #[serde(rename = "Data", default)]
+            pub data: Vec<$data>,
+        }
+
+        impl $crate::SaxoResponse for $name {
+            type RequestType = Request;
+        }
+
+        impl $crate::SaxoResponseOData for $name {
+            fn next(&self) -> Option<Self::RequestType> {
+                Request::from_next(self.next.as_ref()?)
+            }
+        }
+
+        impl std::fmt::Display for $name {
+            fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                write!(f, "SaxoResponse")
+            }
+        }
+    };
+
+    ( $data:ty ) => {
+        saxo_response_list! {struct Response { $data }}
+    };
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -251,9 +331,47 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_request_from_next() {
+        #[allow(dead_code)]
+        mod messages {
+            saxo_request! {"foo/v1/bar/"}
+            saxo_response_list! {String}
+        }
+
+        let response = messages::Response {
+            next: Some(
+                "https://gateway.saxobank.com/sim/openapi/foo/v1/bar/me?$top=2&$skip=2&ClientKey=a%7Cb"
+                    .to_owned(),
+            ),
+            ..Default::default()
+        };
+        let request = response.next().unwrap();
+        assert_eq!(
+            request.argument().to_string(),
+            "me?%24top=2&%24skip=2&ClientKey=a%7Cb"
+        );
+
+        let request = messages::Request::from_next("/openapi/foo/v1/bar?$skip=4").unwrap();
+        assert_eq!(request.argument().to_string(), "?%24skip=4");
+
+        assert!(messages::Request::from_next("/openapi/baz/?$skip=4").is_none());
+        assert!(messages::Response::default().next().is_none());
+    }
+
     #[test]
     fn test_end_point_argument_display() {
-        assert_eq!(format!("{}", EndPointArgument::Id("me")), "me");
+        assert_eq!(format!("{}", EndPointArgument::Id("me".to_owned())), "me");
+        assert_eq!(
+            format!(
+                "{}",
+                EndPointArgument::Query(
+                    String::new(),
+                    vec![("ClientKey".to_owned(), "a|b=".to_owned())]
+                )
+            ),
+            "?ClientKey=a%7Cb%3D"
+        );
         assert_eq!(
             format!("{}", EndPointArgument::OData(ODataParams::default())),
             "?"
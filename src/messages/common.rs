@@ -0,0 +1,371 @@
+//! Structures shared by the responses of several end points.
+
+use crate::error::TimeSpanError;
+
+use chrono::Duration;
+use serde::de::Error;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use strum::{AsRefStr, EnumString};
+
+use std::collections::BTreeSet;
+use std::fmt;
+use std::str::FromStr;
+
+/// Set of field groups to include in a response. End points only return the fields of the
+/// requested groups, so most fields are missing without it.
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct FieldGroups<F: Ord>(BTreeSet<F>);
+
+impl<F: Ord> FieldGroups<F> {
+    #[must_use]
+    pub fn new() -> Self {
+        FieldGroups(BTreeSet::new())
+    }
+
+    #[must_use]
+    pub fn with(mut self, field_group: F) -> Self {
+        self.0.insert(field_group);
+        self
+    }
+
+    #[must_use]
+    pub fn contains(&self, field_group: &F) -> bool {
+        self.0.contains(field_group)
+    }
+}
+
+impl<F: Ord> Default for FieldGroups<F> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<F: Ord> FromIterator<F> for FieldGroups<F> {
+    fn from_iter<T: IntoIterator<Item = F>>(iter: T) -> Self {
+        FieldGroups(iter.into_iter().collect())
+    }
+}
+
+/// Comma separated, as expected by the `FieldGroups` query parameter.
+impl<F: Ord + AsRef<str>> fmt::Display for FieldGroups<F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let field_groups = self.0.iter().map(AsRef::as_ref).collect::<Vec<_>>();
+        write!(f, "{}", field_groups.join(","))
+    }
+}
+
+/// Information about how to display an instrument and its prices.
+#[derive(Deserialize, Debug, Default, Clone, PartialEq)]
+#[serde(rename_all = "PascalCase")]
+pub struct DisplayAndFormat {
+    pub currency: Option<String>,
+    pub decimals: Option<i32>,
+    pub description: Option<String>,
+    pub format: Option<String>,
+    pub order_decimals: Option<i32>,
+    pub symbol: Option<String>,
+}
+
+/// Type of an instrument. Together with its UIC, it identifies an instrument.
+#[derive(EnumString, AsRefStr, Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum AssetType {
+    Bond,
+    CertificateBonus,
+    CertificateCappedBonus,
+    CertificateCappedCapitalProtected,
+    CertificateCappedOutperformance,
+    CertificateConstantLeverage,
+    CertificateDiscount,
+    CertificateExpress,
+    CertificateTracker,
+    CertificateUncappedCapitalProtection,
+    CertificateUncappedOutperformance,
+    CfdIndexOption,
+    CfdOnCompanyWarrant,
+    CfdOnEtc,
+    CfdOnEtf,
+    CfdOnEtn,
+    CfdOnFund,
+    CfdOnFutures,
+    CfdOnIndex,
+    CfdOnRights,
+    CfdOnStock,
+    CompanyWarrant,
+    ContractFutures,
+    Etc,
+    Etf,
+    Etn,
+    Fund,
+    FuturesOption,
+    FuturesStrategy,
+    FxBinaryOption,
+    FxForwards,
+    FxKnockInOption,
+    FxKnockOutOption,
+    FxNoTouchOption,
+    FxOneTouchOption,
+    FxSpot,
+    FxSwap,
+    FxVanillaOption,
+    GuaranteeNote,
+    IpoOnStock,
+    ManagedFund,
+    MiniFuture,
+    MutualFund,
+    PortfolioNote,
+    Rights,
+    SrdOnEtf,
+    SrdOnStock,
+    Stock,
+    StockIndex,
+    StockIndexOption,
+    StockOption,
+    Warrant,
+    WarrantDoubleKnockOut,
+    WarrantKnockOut,
+    WarrantOpenEndKnockOut,
+    WarrantSpread,
+    /// Asset type not known to this crate, kept as sent by the server
+    #[strum(default)]
+    Unknown(String),
+}
+
+impl AssetType {
+    /// Name of the asset type, as used by the end points.
+    #[must_use]
+    pub fn as_str(&self) -> &str {
+        match self {
+            AssetType::Unknown(asset_type) => asset_type,
+            asset_type => asset_type.as_ref(),
+        }
+    }
+}
+
+impl fmt::Display for AssetType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl Serialize for AssetType {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for AssetType {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let asset_type = String::deserialize(deserializer)?;
+        Ok(AssetType::from_str(&asset_type).unwrap_or(AssetType::Unknown(asset_type)))
+    }
+}
+
+/// .NET `TimeSpan`, formatted as `[-][d.]hh:mm:ss[.fffffff]`, e.g. `-05:00:00` for the
+/// offset of a time zone.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct TimeSpan(pub Duration);
+
+impl TimeSpan {
+    #[must_use]
+    pub fn duration(&self) -> Duration {
+        self.0
+    }
+}
+
+impl Default for TimeSpan {
+    fn default() -> Self {
+        TimeSpan(Duration::zero())
+    }
+}
+
+impl From<Duration> for TimeSpan {
+    fn from(duration: Duration) -> Self {
+        TimeSpan(duration)
+    }
+}
+
+impl From<TimeSpan> for Duration {
+    fn from(time_span: TimeSpan) -> Self {
+        time_span.0
+    }
+}
+
+impl FromStr for TimeSpan {
+    type Err = TimeSpanError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = || TimeSpanError(s.to_owned());
+        let number = |part: &str| {
+            if part.is_empty() || !part.bytes().all(|byte| byte.is_ascii_digit()) {
+                return Err(error());
+            }
+            part.parse::<i64>().map_err(|_| error())
+        };
+
+        let (negative, rest) = match s.strip_prefix('-') {
+            Some(rest) => (true, rest),
+            None => (false, s),
+        };
+        let mut parts = rest.split(':');
+        let (Some(hours), Some(minutes), Some(seconds), None) =
+            (parts.next(), parts.next(), parts.next(), parts.next())
+        else {
+            return Err(error());
+        };
+
+        let (days, hours) = match hours.split_once('.') {
+            Some((days, hours)) => (number(days)?, number(hours)?),
+            None => (0, number(hours)?),
+        };
+        let (seconds, ticks) = match seconds.split_once('.') {
+            // The fraction is in 100 nanosecond ticks, with up to 7 digits
+            Some((seconds, fraction)) if (1..=7).contains(&fraction.len()) => {
+                (number(seconds)?, number(&format!("{fraction:0<7}"))?)
+            }
+            Some(_) => return Err(error()),
+            None => (number(seconds)?, 0),
+        };
+        let minutes = number(minutes)?;
+        if hours >= 24 || minutes >= 60 || seconds >= 60 {
+            return Err(error());
+        }
+
+        let seconds = days
+            .checked_mul(86_400)
+            .and_then(|days| days.checked_add(((hours * 60 + minutes) * 60) + seconds))
+            .ok_or_else(error)?;
+        let duration =
+            Duration::try_seconds(seconds).ok_or_else(error)? + Duration::nanoseconds(ticks * 100);
+        Ok(TimeSpan(if negative { -duration } else { duration }))
+    }
+}
+
+impl fmt::Display for TimeSpan {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.0 < Duration::zero() {
+            f.write_str("-")?;
+        }
+        let duration = self.0.abs();
+        let seconds = duration.num_seconds();
+        let ticks = i64::from(duration.subsec_nanos()) / 100;
+
+        let days = seconds / 86_400;
+        if days > 0 {
+            write!(f, "{days}.")?;
+        }
+        write!(
+            f,
+            "{:02}:{:02}:{:02}",
+            seconds / 3600 % 24,
+            seconds / 60 % 60,
+            seconds % 60
+        )?;
+        if ticks > 0 {
+            write!(f, ".{ticks:07}")?;
+        }
+        Ok(())
+    }
+}
+
+impl Serialize for TimeSpan {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for TimeSpan {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let time_span = String::deserialize(deserializer)?;
+        time_span.parse().map_err(D::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use serde_test::{assert_tokens, Token};
+
+    #[derive(Serialize, AsRefStr, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+    enum FieldGroup {
+        Foo,
+        Bar,
+    }
+
+    #[test]
+    fn test_field_groups() {
+        let field_groups = FieldGroups::new()
+            .with(FieldGroup::Bar)
+            .with(FieldGroup::Foo)
+            .with(FieldGroup::Bar);
+
+        assert_eq!(field_groups.to_string(), "Foo,Bar");
+        assert_eq!(
+            serde_json::to_value(&field_groups).unwrap(),
+            serde_json::json!(["Foo", "Bar"])
+        );
+        assert!(FieldGroups::<FieldGroup>::new().to_string().is_empty());
+    }
+
+    #[test]
+    fn test_serde_asset_type() {
+        assert_tokens(&AssetType::FxSpot, &[Token::Str("FxSpot")]);
+        assert_tokens(&AssetType::CfdOnStock, &[Token::Str("CfdOnStock")]);
+        assert_tokens(
+            &AssetType::Unknown("SpaceTrip".to_owned()),
+            &[Token::Str("SpaceTrip")],
+        );
+    }
+
+    #[test]
+    fn test_asset_type_from_str() {
+        assert_eq!(
+            AssetType::from_str("StockOption"),
+            Ok(AssetType::StockOption)
+        );
+        assert_eq!(
+            AssetType::from_str("Foo"),
+            Ok(AssetType::Unknown("Foo".to_owned()))
+        );
+        assert_eq!(AssetType::Unknown("Foo".to_owned()).to_string(), "Foo");
+        assert_eq!(AssetType::FuturesOption.to_string(), "FuturesOption");
+    }
+
+    #[test]
+    fn test_serde_time_span() {
+        assert_tokens(&TimeSpan(Duration::zero()), &[Token::Str("00:00:00")]);
+        assert_tokens(&TimeSpan(Duration::hours(-5)), &[Token::Str("-05:00:00")]);
+        assert_tokens(
+            &TimeSpan(Duration::minutes(5 * 60 + 30)),
+            &[Token::Str("05:30:00")],
+        );
+        assert_tokens(
+            &TimeSpan(Duration::days(1) + Duration::seconds(3723) + Duration::milliseconds(500)),
+            &[Token::Str("1.01:02:03.5000000")],
+        );
+    }
+
+    #[test]
+    fn test_time_span_from_str() {
+        assert_eq!(
+            "-1.00:00:00.25".parse::<TimeSpan>(),
+            Ok(TimeSpan(-(Duration::days(1) + Duration::milliseconds(250))))
+        );
+        for invalid in [
+            "",
+            "05:00",
+            "5:00:00:00",
+            "24:00:00",
+            "00:60:00",
+            "+05:00:00",
+            "00:00:00.",
+            "00:00:00.12345678",
+            "999999999999999999.00:00:00",
+        ] {
+            assert_eq!(
+                invalid.parse::<TimeSpan>(),
+                Err(TimeSpanError(invalid.to_owned()))
+            );
+        }
+    }
+}
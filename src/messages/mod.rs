@@ -1,2 +1,4 @@
+pub mod common;
 pub mod portfolio;
 pub mod reference_data;
+pub mod trading;
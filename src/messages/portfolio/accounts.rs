@@ -0,0 +1,156 @@
+//! Request and response definition for
+//! <https://www.developer.saxo/openapi/referencedocs/port/v1/accounts>
+
+use crate::messages::common::AssetType;
+use crate::streaming::subscription::Subscription;
+use crate::{saxo_request, saxo_response};
+
+use serde::Deserialize;
+use strum::EnumString;
+
+/// Path of the account subscriptions end point.
+pub const SUBSCRIPTIONS: &str = "port/v1/accounts/subscriptions";
+
+saxo_request! {"port/v1/accounts/"}
+saxo_response! {
+    account_group_key: String,
+    account_id: String,
+    account_key: String,
+    account_sub_type: String,
+    account_type: AccountType,
+    active: bool,
+    can_use_cash_positions_as_margin_collateral: bool,
+    cfd_borrowing_costs_active: bool,
+    client_id: String,
+    client_key: String,
+    creation_date: String,
+    currency: String,
+    currency_decimals: u8,
+    direct_market_access: bool,
+    display_name: String,
+    fractional_order_enabled: bool,
+    individual_margining: bool,
+    is_currency_conversion_at_settlement_time: bool,
+    is_margin_trading_allowed: bool,
+    is_shareable: bool,
+    is_trial_account: bool,
+    legal_asset_types: Vec<AssetType>,
+    management_type: String,
+    margin_calculation_method: String,
+    margin_lending_enabled: String,
+    sub_accounts: Vec<Response>,
+    supports_account_value_protection_limit: bool,
+    use_cash_positions_as_margin_collateral: bool
+}
+
+#[derive(EnumString, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AccountType {
+    /// Account used for trading
+    Normal,
+    /// Account only used for settlement of trades on other accounts
+    Settlement,
+    /// Tax account, e.g. for pension savings
+    Tax,
+    /// Account type not known to this crate
+    #[serde(other)]
+    Unknown,
+}
+
+/// Accounts of the logged in user with `me`, or of a client with the `ClientKey` query
+/// parameter.
+pub mod list {
+    use crate::{saxo_request, saxo_response_list};
+
+    saxo_request! {"port/v1/accounts/"}
+    saxo_response_list! {super::Response}
+}
+
+/// Subscription to the accounts of a client, or to a single account.
+#[must_use]
+pub fn subscription(
+    reference_id: &str,
+    client_key: &str,
+    account_key: Option<&str>,
+) -> Subscription {
+    Subscription::for_client(SUBSCRIPTIONS, reference_id, client_key, account_key)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use serde_json::json;
+
+    #[test]
+    fn test_serde_accounts() {
+        let response = json!({
+            "__count": 1,
+            "Data": [
+                {
+                    "AccountGroupKey": "k3W2Fz-G8W9XcVrQiVx3tQ==",
+                    "AccountId": "9073654",
+                    "AccountKey": "LZTc7DdejXODf-WSl2aCyQ==",
+                    "AccountType": "Normal",
+                    "Active": true,
+                    "ClientKey": "7m4I|vtYLUnEGg77o9uQhw==",
+                    "Currency": "EUR",
+                    "CurrencyDecimals": 2,
+                    "IsMarginTradingAllowed": true,
+                    "LegalAssetTypes": ["FxSpot", "Stock", "SpaceTrip"],
+                    "MarginCalculationMethod": "Default",
+                    "SubAccounts": [
+                        { "AccountId": "9073654/USD", "AccountType": "Settlement" }
+                    ]
+                },
+            ]
+        });
+
+        let response = serde_json::from_value::<list::Response>(response).unwrap();
+        let account = &response.data[0];
+
+        assert_eq!(response.count, Some(1));
+        assert_eq!(
+            account.account_key.as_deref(),
+            Some("LZTc7DdejXODf-WSl2aCyQ==")
+        );
+        assert_eq!(account.account_type, Some(AccountType::Normal));
+        assert_eq!(account.is_margin_trading_allowed, Some(true));
+        assert_eq!(
+            account.legal_asset_types.as_deref(),
+            Some(
+                &[
+                    AssetType::FxSpot,
+                    AssetType::Stock,
+                    AssetType::Unknown("SpaceTrip".to_owned())
+                ][..]
+            )
+        );
+        assert_eq!(
+            account.sub_accounts.as_ref().unwrap()[0].account_type,
+            Some(AccountType::Settlement)
+        );
+    }
+
+    #[test]
+    fn test_serde_unknown_account_type() {
+        let response = json!({ "AccountType": "Foo" });
+
+        assert_eq!(
+            serde_json::from_value::<Response>(response)
+                .unwrap()
+                .account_type,
+            Some(AccountType::Unknown)
+        );
+    }
+
+    #[test]
+    fn test_subscription() {
+        let subscription = subscription("accounts", "client", Some("account"));
+
+        assert_eq!(subscription.endpoint, SUBSCRIPTIONS);
+        assert_eq!(
+            subscription.arguments,
+            json!({ "ClientKey": "client", "AccountKey": "account" })
+        );
+    }
+}
@@ -0,0 +1,109 @@
+//! Request and response definition for
+//! <https://www.developer.saxo/openapi/referencedocs/port/v1/balances>
+
+use crate::streaming::subscription::Subscription;
+use crate::{saxo_request, saxo_response};
+
+use serde::Deserialize;
+
+/// Path of the balance subscriptions end point.
+pub const SUBSCRIPTIONS: &str = "port/v1/balances/subscriptions";
+
+// Balance of the logged in user with `me`, or of a client, account or account group with the
+// `ClientKey`, `AccountKey` and `AccountGroupKey` query parameters
+saxo_request! {"port/v1/balances/"}
+saxo_response! {
+    calculation_reliability: String,
+    cash_available_for_trading: f64,
+    cash_balance: f64,
+    cash_blocked: f64,
+    changes_scheduled: bool,
+    closed_positions_count: i32,
+    collateral_available: f64,
+    cost_to_close_positions: f64,
+    currency: String,
+    currency_decimals: u8,
+    initial_margin: InitialMargin,
+    is_portfolio_margin_model_simple: bool,
+    margin_available_for_trading: f64,
+    margin_collateral_not_available: f64,
+    margin_exposure_coverage_pct: f64,
+    margin_net_exposure: f64,
+    margin_used_by_current_positions: f64,
+    margin_utilization_pct: f64,
+    net_equity_for_margin: f64,
+    net_positions_count: i32,
+    non_margin_positions_value: f64,
+    open_positions_count: i32,
+    option_premiums_market_value: f64,
+    orders_count: i32,
+    other_collateral: f64,
+    settlement_value: f64,
+    total_value: f64,
+    transactions_not_booked: f64,
+    unrealized_margin_closed_profit_loss: f64,
+    unrealized_margin_open_profit_loss: f64,
+    unrealized_margin_profit_loss: f64,
+    unrealized_positions_value: f64
+}
+
+/// Margin figures based on the initial margin requirements.
+#[derive(Deserialize, Debug, Default, PartialEq)]
+#[serde(rename_all = "PascalCase")]
+pub struct InitialMargin {
+    pub collateral_available: Option<f64>,
+    pub margin_available: Option<f64>,
+    pub margin_collateral_not_available: Option<f64>,
+    pub margin_used_by_current_positions: Option<f64>,
+    pub margin_utilization_pct: Option<f64>,
+    pub net_equity_for_margin: Option<f64>,
+    pub other_collateral_deduction: Option<f64>,
+}
+
+/// Subscription to the balance of a client or account.
+#[must_use]
+pub fn subscription(
+    reference_id: &str,
+    client_key: &str,
+    account_key: Option<&str>,
+) -> Subscription {
+    Subscription::for_client(SUBSCRIPTIONS, reference_id, client_key, account_key)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use serde_json::json;
+
+    #[test]
+    fn test_serde_balances() {
+        let response = json!({
+            "CalculationReliability": "Ok",
+            "CashBalance": 99_807.8,
+            "Currency": "EUR",
+            "CurrencyDecimals": 2,
+            "InitialMargin": {
+                "MarginAvailable": 99_652.38,
+                "MarginUsedByCurrentPositions": -155.42,
+                "MarginUtilizationPct": 0.16,
+                "NetEquityForMargin": 99_807.8
+            },
+            "MarginUtilizationPct": 0.16,
+            "TotalValue": 99_807.8,
+            "UnrealizedMarginProfitLoss": -12.5
+        });
+
+        let response = serde_json::from_value::<Response>(response).unwrap();
+
+        assert_eq!(response.total_value, Some(99_807.8));
+        assert_eq!(response.unrealized_margin_profit_loss, Some(-12.5));
+        assert_eq!(
+            response
+                .initial_margin
+                .unwrap()
+                .margin_used_by_current_positions,
+            Some(-155.42)
+        );
+    }
+}
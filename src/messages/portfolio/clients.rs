@@ -1,6 +1,7 @@
 //! Request and response definition for
 //! <https://www.developer.saxo/openapi/referencedocs/port/v1/clients>
 
+use crate::messages::common::AssetType;
 use crate::{saxo_request, saxo_response};
 
 saxo_request! {"port/v1/clients/"}
@@ -17,7 +18,7 @@ saxo_response! {
     force_open_default_value: bool,
     is_margin_trading_allowed: bool,
     is_variation_margin_eligible: bool,
-    legal_asset_types: Vec<String>,
+    legal_asset_types: Vec<AssetType>,
     legal_asset_types_are_indicative: bool,
     margin_calculation_method: String,
     margin_monitoring_mode: String,
@@ -0,0 +1,204 @@
+//! Request and response definition for
+//! <https://www.developer.saxo/openapi/referencedocs/port/v1/closedpositions>
+
+use crate::messages::common::{AssetType, DisplayAndFormat, FieldGroups};
+use crate::{saxo_request, saxo_response};
+
+use serde::{Deserialize, Serialize};
+use strum::AsRefStr;
+
+use std::collections::HashMap;
+
+// Single closed position by id, with the `ClientKey` and `FieldGroups` query parameters
+saxo_request! {"port/v1/closedpositions/"}
+saxo_response! {
+    closed_position: ClosedPosition,
+    closed_position_unique_id: String,
+    display_and_format: DisplayAndFormat,
+    net_position_id: String
+}
+
+#[derive(Serialize, AsRefStr, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum FieldGroup {
+    ClosedPosition,
+    ClosedPositionDetails,
+    DisplayAndFormat,
+    ExchangeInfo,
+}
+
+impl FieldGroup {
+    /// Data and formatting of the closed positions.
+    #[must_use]
+    pub fn defaults() -> FieldGroups<FieldGroup> {
+        [FieldGroup::ClosedPosition, FieldGroup::DisplayAndFormat]
+            .into_iter()
+            .collect()
+    }
+}
+
+/// A position closed by a trade in the opposite direction.
+#[derive(Deserialize, Debug, Default, Clone, PartialEq)]
+#[serde(rename_all = "PascalCase")]
+pub struct ClosedPosition {
+    pub account_id: Option<String>,
+    pub amount: Option<f64>,
+    pub asset_type: Option<AssetType>,
+    pub buy_or_sell: Option<String>,
+    pub client_id: Option<String>,
+    pub closed_profit_loss: Option<f64>,
+    pub closed_profit_loss_in_base_currency: Option<f64>,
+    pub closing_market_value: Option<f64>,
+    pub closing_market_value_in_base_currency: Option<f64>,
+    pub closing_method: Option<String>,
+    pub closing_position_id: Option<String>,
+    pub closing_price: Option<f64>,
+    pub conversion_rate_instrument_to_base_settled_closing: Option<bool>,
+    pub conversion_rate_instrument_to_base_settled_opening: Option<bool>,
+    pub cost_closing: Option<f64>,
+    pub cost_closing_in_base_currency: Option<f64>,
+    pub cost_opening: Option<f64>,
+    pub cost_opening_in_base_currency: Option<f64>,
+    pub execution_time_close: Option<String>,
+    pub execution_time_open: Option<String>,
+    pub opening_position_id: Option<String>,
+    pub open_price: Option<f64>,
+    pub profit_loss_on_trade: Option<f64>,
+    pub profit_loss_on_trade_in_base_currency: Option<f64>,
+    pub uic: Option<i32>,
+}
+
+/// Instrument of a position, identified by its UIC and asset type.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Instrument {
+    pub uic: i32,
+    pub asset_type: AssetType,
+}
+
+/// Closed positions of the logged in user with `me`, or of a client or account with the
+/// `ClientKey`, `AccountKey` and `FieldGroups` query parameters. Paged with `$top` and `$skip`.
+pub mod list {
+    use super::Instrument;
+    use crate::{saxo_request, saxo_response_list};
+
+    use std::collections::HashMap;
+
+    saxo_request! {"port/v1/closedpositions/"}
+    saxo_response_list! {super::Response}
+
+    impl Response {
+        /// Realized profit/loss in the base currency of the account, summed per instrument.
+        /// Positions without the `ClosedPosition` field group are left out.
+        #[must_use]
+        pub fn realized_profit_loss(&self) -> HashMap<Instrument, f64> {
+            super::realized_profit_loss(
+                self.data
+                    .iter()
+                    .filter_map(|position| position.closed_position.as_ref()),
+            )
+        }
+    }
+}
+
+/// Realized profit/loss in the base currency of the account, summed per instrument, e.g. over
+/// the closed positions of several pages.
+pub fn realized_profit_loss<'a>(
+    positions: impl IntoIterator<Item = &'a ClosedPosition>,
+) -> HashMap<Instrument, f64> {
+    let mut profit_loss = HashMap::new();
+
+    for position in positions {
+        if let (Some(uic), Some(asset_type), Some(closed_profit_loss)) = (
+            position.uic,
+            position.asset_type.as_ref(),
+            position.closed_profit_loss_in_base_currency,
+        ) {
+            let instrument = Instrument {
+                uic,
+                asset_type: asset_type.clone(),
+            };
+            *profit_loss.entry(instrument).or_default() += closed_profit_loss;
+        }
+    }
+
+    profit_loss
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::SaxoResponseOData;
+    use serde_json::json;
+
+    fn response() -> list::Response {
+        let response = json!({
+            "__count": 3,
+            "__next": "/openapi/port/v1/closedpositions/me?$top=3&$skip=3&FieldGroups=ClosedPosition",
+            "Data": [
+                {
+                    "ClosedPosition": {
+                        "AssetType": "FxSpot",
+                        "ClosedProfitLoss": 120.0,
+                        "ClosedProfitLossInBaseCurrency": 110.5,
+                        "Uic": 21
+                    },
+                    "ClosedPositionUniqueId": "1-2"
+                },
+                {
+                    "ClosedPosition": {
+                        "AssetType": "FxSpot",
+                        "ClosedProfitLossInBaseCurrency": -10.5,
+                        "Uic": 21
+                    },
+                    "ClosedPositionUniqueId": "3-4"
+                },
+                {
+                    "ClosedPosition": {
+                        "AssetType": "Stock",
+                        "ClosedProfitLossInBaseCurrency": 42.0,
+                        "Uic": 211
+                    },
+                    "ClosedPositionUniqueId": "5-6"
+                }
+            ]
+        });
+
+        serde_json::from_value(response).unwrap()
+    }
+
+    #[test]
+    fn test_serde_closed_positions() {
+        let response = response();
+
+        assert_eq!(response.count, Some(3));
+        assert_eq!(
+            response.data[0].closed_position_unique_id.as_deref(),
+            Some("1-2")
+        );
+        assert_eq!(
+            response.next().unwrap().argument().to_string(),
+            "me?%24top=3&%24skip=3&FieldGroups=ClosedPosition"
+        );
+    }
+
+    #[test]
+    fn test_realized_profit_loss() {
+        let profit_loss = response().realized_profit_loss();
+
+        assert_eq!(profit_loss.len(), 2);
+        assert_eq!(
+            profit_loss.get(&Instrument {
+                uic: 21,
+                asset_type: AssetType::FxSpot
+            }),
+            Some(&100.0)
+        );
+        assert_eq!(
+            profit_loss.get(&Instrument {
+                uic: 211,
+                asset_type: AssetType::Stock
+            }),
+            Some(&42.0)
+        );
+    }
+}
@@ -1,2 +1,8 @@
+pub mod accounts;
+pub mod balances;
 pub mod clients;
+pub mod closedpositions;
+pub mod netpositions;
+pub mod orders;
+pub mod positions;
 pub mod users;
@@ -0,0 +1,160 @@
+//! Request and response definition for
+//! <https://www.developer.saxo/openapi/referencedocs/port/v1/netpositions>
+
+use crate::messages::common::{AssetType, DisplayAndFormat, FieldGroups};
+use crate::streaming::subscription::Subscription;
+use crate::{saxo_request, saxo_response};
+
+use serde::{Deserialize, Serialize};
+use strum::AsRefStr;
+
+/// Path of the net position subscriptions end point.
+pub const SUBSCRIPTIONS: &str = "port/v1/netpositions/subscriptions";
+
+// Single net position by id, with the `ClientKey` and `FieldGroups` query parameters
+saxo_request! {"port/v1/netpositions/"}
+saxo_response! {
+    display_and_format: DisplayAndFormat,
+    net_position_base: NetPositionBase,
+    net_position_id: String,
+    net_position_view: NetPositionView
+}
+
+#[derive(Serialize, AsRefStr, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum FieldGroup {
+    DisplayAndFormat,
+    ExchangeInfo,
+    Greeks,
+    NetPositionBase,
+    NetPositionView,
+    SinglePosition,
+    SinglePositionBase,
+    SinglePositionView,
+}
+
+impl FieldGroup {
+    /// Static data, dynamic data and formatting of the net positions.
+    #[must_use]
+    pub fn defaults() -> FieldGroups<FieldGroup> {
+        [
+            FieldGroup::DisplayAndFormat,
+            FieldGroup::NetPositionBase,
+            FieldGroup::NetPositionView,
+        ]
+        .into_iter()
+        .collect()
+    }
+}
+
+/// Static data of the positions aggregated per instrument.
+#[derive(Deserialize, Debug, Default, Clone, PartialEq)]
+#[serde(rename_all = "PascalCase")]
+pub struct NetPositionBase {
+    pub account_id: Option<String>,
+    pub account_key: Option<String>,
+    pub amount: Option<f64>,
+    pub asset_type: Option<AssetType>,
+    pub can_be_closed: Option<bool>,
+    pub client_id: Option<String>,
+    pub is_market_open: Option<bool>,
+    pub number_of_related_orders: Option<i32>,
+    pub positions_account: Option<String>,
+    pub single_position_id: Option<String>,
+    pub single_position_status: Option<String>,
+    pub uic: Option<i32>,
+    pub value_date: Option<String>,
+}
+
+/// Dynamic data of the positions aggregated per instrument.
+#[derive(Deserialize, Debug, Default, Clone, PartialEq)]
+#[serde(rename_all = "PascalCase")]
+pub struct NetPositionView {
+    pub average_open_price: Option<f64>,
+    pub calculation_reliability: Option<String>,
+    pub current_price: Option<f64>,
+    pub current_price_delay_minutes: Option<i32>,
+    pub current_price_type: Option<String>,
+    pub exposure: Option<f64>,
+    pub exposure_currency: Option<String>,
+    pub exposure_in_base_currency: Option<f64>,
+    pub market_value: Option<f64>,
+    pub position_count: Option<i32>,
+    pub profit_loss_on_trade: Option<f64>,
+    pub profit_loss_on_trade_in_base_currency: Option<f64>,
+    pub status: Option<String>,
+    pub trade_costs_total: Option<f64>,
+    pub trade_costs_total_in_base_currency: Option<f64>,
+}
+
+/// Net positions of the logged in user with `me`, or of a client or account with the
+/// `ClientKey`, `AccountKey` and `FieldGroups` query parameters.
+pub mod list {
+    use crate::{saxo_request, saxo_response_list};
+
+    saxo_request! {"port/v1/netpositions/"}
+    saxo_response_list! {super::Response}
+}
+
+/// Subscription to the net positions of a client or account.
+#[must_use]
+pub fn subscription(
+    reference_id: &str,
+    client_key: &str,
+    account_key: Option<&str>,
+    field_groups: &FieldGroups<FieldGroup>,
+) -> Subscription {
+    Subscription::for_client(SUBSCRIPTIONS, reference_id, client_key, account_key)
+        .field_groups(field_groups)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use serde_json::json;
+
+    #[test]
+    fn test_serde_net_positions() {
+        let response = json!({
+            "Data": [
+                {
+                    "NetPositionBase": {
+                        "AccountId": "192134INET",
+                        "Amount": 200_000.0,
+                        "AssetType": "FxSpot",
+                        "NumberOfRelatedOrders": 0,
+                        "PositionsAccount": "192134INET",
+                        "Uic": 21
+                    },
+                    "NetPositionId": "EURUSD__FxSpot",
+                    "NetPositionView": {
+                        "AverageOpenPrice": 1.0812,
+                        "PositionCount": 2,
+                        "ProfitLossOnTrade": -40.0,
+                        "Status": "Open"
+                    }
+                }
+            ]
+        });
+
+        let response = serde_json::from_value::<list::Response>(response).unwrap();
+        let net_position = &response.data[0];
+
+        assert_eq!(
+            net_position.net_position_id.as_deref(),
+            Some("EURUSD__FxSpot")
+        );
+        assert_eq!(
+            net_position.net_position_base.as_ref().unwrap().amount,
+            Some(200_000.0)
+        );
+        assert_eq!(
+            net_position
+                .net_position_view
+                .as_ref()
+                .unwrap()
+                .position_count,
+            Some(2)
+        );
+    }
+}
@@ -0,0 +1,375 @@
+//! Request and response definition for
+//! <https://www.developer.saxo/openapi/referencedocs/port/v1/orders>
+
+use crate::messages::common::{AssetType, DisplayAndFormat, FieldGroups};
+use crate::streaming::subscription::Subscription;
+use crate::{saxo_request, saxo_response};
+
+use serde::{Deserialize, Serialize};
+use strum::{AsRefStr, EnumString};
+
+/// Path of the order subscriptions end point.
+pub const SUBSCRIPTIONS: &str = "port/v1/orders/subscriptions";
+
+// Single order with `{ClientKey}/{OrderId}` and the `FieldGroups` query parameter
+saxo_request! {"port/v1/orders/"}
+saxo_response! {
+    account_id: String,
+    account_key: String,
+    amount: f64,
+    asset_type: AssetType,
+    buy_sell: BuySell,
+    calculation_reliability: String,
+    client_key: String,
+    correlation_key: String,
+    current_price: f64,
+    current_price_delay_minutes: i32,
+    current_price_type: String,
+    display_and_format: DisplayAndFormat,
+    distance_to_market: f64,
+    duration: Duration,
+    external_reference: String,
+    is_force_open: bool,
+    is_market_open: bool,
+    market_price: f64,
+    multi_leg_order_details: MultiLegOrderDetails,
+    non_tradable_reason: String,
+    open_order_type: OrderType,
+    options_data: OptionsData,
+    order_amount_type: String,
+    order_id: String,
+    order_relation: String,
+    order_time: String,
+    price: f64,
+    related_open_orders: Vec<RelatedOrder>,
+    status: Status,
+    stop_limit_price: f64,
+    trailing_stop_distance_to_market: f64,
+    trailing_stop_step: f64,
+    uic: i32
+}
+
+#[derive(Serialize, AsRefStr, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum FieldGroup {
+    DisplayAndFormat,
+    ExchangeInfo,
+    Greeks,
+}
+
+/// Order placed along with another one, e.g. the stop loss of an entry order.
+#[derive(Deserialize, Debug, Default, Clone, PartialEq)]
+#[serde(rename_all = "PascalCase")]
+pub struct RelatedOrder {
+    pub amount: Option<f64>,
+    pub duration: Option<Duration>,
+    pub open_order_type: Option<OrderType>,
+    pub order_id: Option<String>,
+    pub order_price: Option<f64>,
+    pub status: Option<Status>,
+}
+
+/// Strategy a leg of a multi-leg order belongs to. Every leg is listed as a separate order.
+#[derive(Deserialize, Debug, Default, Clone, PartialEq)]
+#[serde(rename_all = "PascalCase")]
+pub struct MultiLegOrderDetails {
+    pub amount: Option<f64>,
+    pub buy_sell: Option<BuySell>,
+    pub description: Option<String>,
+    pub leg_count: Option<i32>,
+    pub multi_leg_order_id: Option<String>,
+    pub strategy_type: Option<String>,
+}
+
+/// Contract of an option order.
+#[derive(Deserialize, Debug, Default, Clone, PartialEq)]
+#[serde(rename_all = "PascalCase")]
+pub struct OptionsData {
+    pub expiry_date: Option<String>,
+    pub put_call: Option<PutCall>,
+    pub strike: Option<f64>,
+    pub underlying: Option<String>,
+    pub underlying_uic: Option<i32>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "PascalCase")]
+pub struct Duration {
+    pub duration_type: OrderDuration,
+    /// Only set for `GoodTillDate` orders
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expiration_date_time: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expiration_date_contains_time: Option<bool>,
+}
+
+impl From<OrderDuration> for Duration {
+    fn from(duration_type: OrderDuration) -> Self {
+        Duration {
+            duration_type,
+            expiration_date_time: None,
+            expiration_date_contains_time: None,
+        }
+    }
+}
+
+#[derive(EnumString, Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OrderType {
+    /// Algorithmic order executed according to a strategy
+    Algorithmic,
+    /// Order added to the system by the back office
+    DealCapture,
+    /// Stop order with a guaranteed fill price
+    GuaranteedStop,
+    /// Buy or sell at the given price or better
+    Limit,
+    /// Buy or sell at the best available price
+    Market,
+    /// Stop order that becomes a market order once the stop price is reached
+    Stop,
+    /// Stop order placed only if the order it is related to is filled
+    StopIfTraded,
+    /// Stop order that becomes a limit order once the stop price is reached
+    StopLimit,
+    /// Switch between two mutual funds
+    Switch,
+    /// Stop order following the market at a fixed distance
+    TrailingStop,
+    /// Trailing stop order placed only if the order it is related to is filled
+    TrailingStopIfTraded,
+    /// Transfer of a mutual fund to another bank
+    Traspaso,
+    /// Transfer of a mutual fund from another bank
+    TraspasoIn,
+    /// Order placed once the price breaks out of a range
+    TriggerBreakout,
+    /// Limit order placed once a trigger price is reached
+    TriggerLimit,
+    /// Stop order placed once a trigger price is reached
+    TriggerStop,
+    /// Order type not known to this crate, which can't be sent back to the server
+    #[serde(other, skip_serializing)]
+    Unknown,
+}
+
+#[derive(EnumString, Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BuySell {
+    Buy,
+    Sell,
+}
+
+#[derive(EnumString, Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PutCall {
+    /// Right to buy the underlying at the strike price
+    Call,
+    /// Right to sell the underlying at the strike price
+    Put,
+}
+
+#[derive(EnumString, Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OrderDuration {
+    /// Filled at the closing price of the exchange, or cancelled
+    AtTheClose,
+    /// Filled at the opening price of the exchange, or cancelled
+    AtTheOpening,
+    /// Valid until the end of the trading day
+    DayOrder,
+    /// Filled completely and immediately, or cancelled
+    FillOrKill,
+    /// Valid for a period given by the exchange
+    GoodForPeriod,
+    /// Valid until cancelled
+    GoodTillCancel,
+    /// Valid until the given expiration date
+    GoodTillDate,
+    /// Filled as much as possible immediately, and the rest cancelled
+    ImmediateOrCancel,
+}
+
+#[derive(EnumString, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Status {
+    /// Order has been filled
+    Filled,
+    /// Order is being placed
+    LockedPlacementPending,
+    /// Order is not working, e.g. a related order waiting for its entry order to be filled
+    NotWorking,
+    /// Order is working in the market
+    Working,
+    /// The status of the order is unknown, or not known to this crate
+    #[serde(other)]
+    Unknown,
+}
+
+/// Orders of the logged in user with `me`, or of a client or account with the `ClientKey`,
+/// `AccountKey` and `FieldGroups` query parameters.
+pub mod list {
+    use crate::{saxo_request, saxo_response_list};
+
+    use std::collections::BTreeMap;
+
+    saxo_request! {"port/v1/orders/"}
+    saxo_response_list! {super::Response}
+
+    impl Response {
+        /// Legs of the multi-leg orders, grouped by multi-leg order id.
+        #[must_use]
+        pub fn multi_leg_orders(&self) -> BTreeMap<&str, Vec<&super::Response>> {
+            let mut orders = BTreeMap::<_, Vec<_>>::new();
+            for order in &self.data {
+                if let Some(id) = order
+                    .multi_leg_order_details
+                    .as_ref()
+                    .and_then(|details| details.multi_leg_order_id.as_deref())
+                {
+                    orders.entry(id).or_default().push(order);
+                }
+            }
+            orders
+        }
+    }
+}
+
+/// Subscription to the working orders of a client or account.
+#[must_use]
+pub fn subscription(
+    reference_id: &str,
+    client_key: &str,
+    account_key: Option<&str>,
+    field_groups: &FieldGroups<FieldGroup>,
+) -> Subscription {
+    Subscription::for_client(SUBSCRIPTIONS, reference_id, client_key, account_key)
+        .field_groups(field_groups)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use serde_json::json;
+
+    use std::str::FromStr;
+
+    #[test]
+    fn test_serde_orders() {
+        let response = json!({
+            "__count": 1,
+            "Data": [
+                {
+                    "AccountId": "192134INET",
+                    "Amount": 100_000.0,
+                    "AssetType": "FxSpot",
+                    "BuySell": "Buy",
+                    "Duration": { "DurationType": "GoodTillCancel" },
+                    "OpenOrderType": "Limit",
+                    "OrderId": "49201931",
+                    "Price": 1.05,
+                    "RelatedOpenOrders": [
+                        {
+                            "Amount": 100_000.0,
+                            "Duration": { "DurationType": "GoodTillCancel" },
+                            "OpenOrderType": "Stop",
+                            "OrderId": "49201932",
+                            "OrderPrice": 1.04,
+                            "Status": "NotWorking"
+                        }
+                    ],
+                    "Status": "Working",
+                    "Uic": 21
+                }
+            ]
+        });
+
+        let response = serde_json::from_value::<list::Response>(response).unwrap();
+        let order = &response.data[0];
+
+        assert_eq!(order.buy_sell, Some(BuySell::Buy));
+        assert_eq!(order.open_order_type, Some(OrderType::Limit));
+        assert_eq!(order.status, Some(Status::Working));
+        assert_eq!(
+            order.duration.as_ref().unwrap().duration_type,
+            OrderDuration::GoodTillCancel
+        );
+        assert_eq!(
+            order.related_open_orders.as_ref().unwrap()[0].open_order_type,
+            Some(OrderType::Stop)
+        );
+    }
+
+    #[test]
+    fn test_serde_multi_leg_order() {
+        let response = json!({
+            "AssetType": "StockOption",
+            "MultiLegOrderDetails": {
+                "Amount": 1.0,
+                "BuySell": "Buy",
+                "Description": "Vertical Call Spread",
+                "LegCount": 2,
+                "MultiLegOrderId": "5001",
+                "StrategyType": "Vertical"
+            },
+            "OptionsData": {
+                "ExpiryDate": "2023-06-16T00:00:00Z",
+                "PutCall": "Call",
+                "Strike": 180.0,
+                "UnderlyingUic": 211
+            },
+            "OrderId": "49201933"
+        });
+
+        let order = serde_json::from_value::<Response>(response).unwrap();
+
+        assert_eq!(
+            order
+                .multi_leg_order_details
+                .unwrap()
+                .multi_leg_order_id
+                .as_deref(),
+            Some("5001")
+        );
+        assert_eq!(order.options_data.unwrap().put_call, Some(PutCall::Call));
+    }
+
+    #[test]
+    fn test_multi_leg_orders() {
+        let leg = |order_id: &str, multi_leg_order_id: Option<&str>| {
+            json!({
+                "MultiLegOrderDetails": { "MultiLegOrderId": multi_leg_order_id },
+                "OrderId": order_id
+            })
+        };
+        let response = json!({
+            "Data": [leg("1", Some("5001")), leg("2", None), leg("3", Some("5001"))]
+        });
+
+        let response = serde_json::from_value::<list::Response>(response).unwrap();
+        let orders = response.multi_leg_orders();
+
+        assert_eq!(orders.len(), 1);
+        assert_eq!(
+            orders["5001"]
+                .iter()
+                .map(|order| order.order_id.as_deref().unwrap())
+                .collect::<Vec<_>>(),
+            vec!["1", "3"]
+        );
+    }
+
+    #[test]
+    fn test_order_type_from_str() {
+        assert_eq!(OrderType::from_str("StopLimit"), Ok(OrderType::StopLimit));
+        assert!(OrderType::from_str("Foo").is_err());
+    }
+
+    #[test]
+    fn test_serde_unknown_order_type_and_status() {
+        let response = json!({
+            "Data": [{ "OpenOrderType": "StopIfBid", "OrderId": "1", "Status": "Parked" }]
+        });
+
+        let response = serde_json::from_value::<list::Response>(response).unwrap();
+
+        assert_eq!(response.data[0].open_order_type, Some(OrderType::Unknown));
+        assert_eq!(response.data[0].status, Some(Status::Unknown));
+        assert!(serde_json::to_value(OrderType::Unknown).is_err());
+    }
+}
@@ -0,0 +1,189 @@
+//! Request and response definition for
+//! <https://www.developer.saxo/openapi/referencedocs/port/v1/positions>
+
+use crate::messages::common::{AssetType, DisplayAndFormat, FieldGroups};
+use crate::streaming::subscription::Subscription;
+use crate::{saxo_request, saxo_response};
+
+use serde::{Deserialize, Serialize};
+use strum::AsRefStr;
+
+/// Path of the position subscriptions end point.
+pub const SUBSCRIPTIONS: &str = "port/v1/positions/subscriptions";
+
+// Single position by id, with the `ClientKey` and `FieldGroups` query parameters
+saxo_request! {"port/v1/positions/"}
+saxo_response! {
+    display_and_format: DisplayAndFormat,
+    net_position_id: String,
+    position_base: PositionBase,
+    position_id: String,
+    position_view: PositionView
+}
+
+#[derive(Serialize, AsRefStr, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum FieldGroup {
+    DisplayAndFormat,
+    ExchangeInfo,
+    Greeks,
+    PositionBase,
+    PositionIdOnly,
+    PositionView,
+}
+
+impl FieldGroup {
+    /// Static data, dynamic data and formatting of the positions.
+    #[must_use]
+    pub fn defaults() -> FieldGroups<FieldGroup> {
+        [
+            FieldGroup::DisplayAndFormat,
+            FieldGroup::PositionBase,
+            FieldGroup::PositionView,
+        ]
+        .into_iter()
+        .collect()
+    }
+}
+
+/// Static data of a position, which doesn't change while it is open.
+#[derive(Deserialize, Debug, Default, Clone, PartialEq)]
+#[serde(rename_all = "PascalCase")]
+pub struct PositionBase {
+    pub account_id: Option<String>,
+    pub account_key: Option<String>,
+    pub amount: Option<f64>,
+    pub asset_type: Option<AssetType>,
+    pub can_be_closed: Option<bool>,
+    pub client_id: Option<String>,
+    pub close_conversion_rate_settled: Option<bool>,
+    pub correlation_key: Option<String>,
+    pub execution_time_open: Option<String>,
+    pub is_force_open: Option<bool>,
+    pub is_market_open: Option<bool>,
+    pub locked_by_back_office: Option<bool>,
+    pub open_price: Option<f64>,
+    pub open_price_including_costs: Option<f64>,
+    pub source_order_id: Option<String>,
+    pub status: Option<String>,
+    pub uic: Option<i32>,
+    pub value_date: Option<String>,
+}
+
+/// Dynamic data of a position, e.g. its current price and profit/loss.
+#[derive(Deserialize, Debug, Default, Clone, PartialEq)]
+#[serde(rename_all = "PascalCase")]
+pub struct PositionView {
+    pub calculation_reliability: Option<String>,
+    pub conversion_rate_current: Option<f64>,
+    pub conversion_rate_open: Option<f64>,
+    pub current_price: Option<f64>,
+    pub current_price_delay_minutes: Option<i32>,
+    pub current_price_type: Option<String>,
+    pub exposure: Option<f64>,
+    pub exposure_currency: Option<String>,
+    pub exposure_in_base_currency: Option<f64>,
+    pub instrument_price_day_percent_change: Option<f64>,
+    pub market_value: Option<f64>,
+    pub profit_loss_on_trade: Option<f64>,
+    pub profit_loss_on_trade_in_base_currency: Option<f64>,
+    pub trade_costs_total: Option<f64>,
+    pub trade_costs_total_in_base_currency: Option<f64>,
+}
+
+/// Positions of the logged in user with `me`, or of a client or account with the `ClientKey`,
+/// `AccountKey` and `FieldGroups` query parameters.
+pub mod list {
+    use crate::{saxo_request, saxo_response_list};
+
+    saxo_request! {"port/v1/positions/"}
+    saxo_response_list! {super::Response}
+}
+
+/// Subscription to the positions of a client or account.
+#[must_use]
+pub fn subscription(
+    reference_id: &str,
+    client_key: &str,
+    account_key: Option<&str>,
+    field_groups: &FieldGroups<FieldGroup>,
+) -> Subscription {
+    Subscription::for_client(SUBSCRIPTIONS, reference_id, client_key, account_key)
+        .field_groups(field_groups)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use serde_json::json;
+
+    #[test]
+    fn test_serde_positions() {
+        let response = json!({
+            "__count": 1,
+            "Data": [
+                {
+                    "DisplayAndFormat": {
+                        "Currency": "USD",
+                        "Decimals": 4,
+                        "Description": "British Pound/US Dollar",
+                        "Format": "AllowDecimalPips",
+                        "Symbol": "GBPUSD"
+                    },
+                    "NetPositionId": "GBPUSD__FxSpot",
+                    "PositionBase": {
+                        "AccountId": "192134INET",
+                        "Amount": 100_000.0,
+                        "AssetType": "FxSpot",
+                        "CanBeClosed": true,
+                        "OpenPrice": 1.2571,
+                        "Status": "Open",
+                        "Uic": 31
+                    },
+                    "PositionId": "1019942425",
+                    "PositionView": {
+                        "CalculationReliability": "Ok",
+                        "CurrentPrice": 1.2586,
+                        "ProfitLossOnTrade": 150.0
+                    }
+                }
+            ]
+        });
+
+        let response = serde_json::from_value::<list::Response>(response).unwrap();
+        let position = &response.data[0];
+
+        assert_eq!(position.position_id.as_deref(), Some("1019942425"));
+        assert_eq!(position.position_base.as_ref().unwrap().uic, Some(31));
+        assert_eq!(
+            position
+                .position_view
+                .as_ref()
+                .unwrap()
+                .profit_loss_on_trade,
+            Some(150.0)
+        );
+        assert_eq!(
+            position
+                .display_and_format
+                .as_ref()
+                .unwrap()
+                .symbol
+                .as_deref(),
+            Some("GBPUSD")
+        );
+    }
+
+    #[test]
+    fn test_subscription() {
+        let subscription = subscription("positions", "client", None, &FieldGroup::defaults());
+
+        assert_eq!(
+            subscription.arguments,
+            json!({
+                "ClientKey": "client",
+                "FieldGroups": ["DisplayAndFormat", "PositionBase", "PositionView"]
+            })
+        );
+    }
+}
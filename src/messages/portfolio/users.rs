@@ -1,6 +1,7 @@
 //! Request and response definition for
 //! <https://www.developer.saxo/openapi/referencedocs/port/v1/users>
 
+use crate::messages::common::AssetType;
 use crate::{saxo_request, saxo_response};
 
 saxo_request! {"port/v1/users/"}
@@ -10,7 +11,7 @@ saxo_response! {
     language: String,
     last_login_status: String,
     last_login_time: String,
-    legal_asset_types: Vec<String>,
+    legal_asset_types: Vec<AssetType>,
     market_data_via_open_api_terms_accepted: bool,
     name: String,
     time_zone_id: i32,
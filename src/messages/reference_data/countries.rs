@@ -0,0 +1,21 @@
+//! Request and response definition for
+//! <https://www.developer.saxo/openapi/referencedocs/ref/v1/countries>
+
+use crate::{saxo_request, saxo_response_list};
+
+use serde::Deserialize;
+
+saxo_request! {"ref/v1/countries/"}
+saxo_response_list! {Country}
+
+#[derive(Deserialize, Debug, Default, Clone, PartialEq)]
+#[serde(rename_all = "PascalCase")]
+pub struct Country {
+    /// ISO 3166 alpha-3 code, e.g. `DNK`
+    pub a3: Option<String>,
+    /// ISO 3166 alpha-2 code, e.g. `DK`
+    pub country_code: Option<String>,
+    pub display_name: Option<String>,
+    pub name: Option<String>,
+    pub numeric_code: Option<i32>,
+}
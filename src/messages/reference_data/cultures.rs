@@ -0,0 +1,17 @@
+//! Request and response definition for
+//! <https://www.developer.saxo/openapi/referencedocs/ref/v1/cultures>
+
+use crate::{saxo_request, saxo_response_list};
+
+use serde::Deserialize;
+
+saxo_request! {"ref/v1/cultures/"}
+saxo_response_list! {Culture}
+
+#[derive(Deserialize, Debug, Default, Clone, PartialEq)]
+#[serde(rename_all = "PascalCase")]
+pub struct Culture {
+    /// Culture code, e.g. `da-DK`
+    pub culture_code: Option<String>,
+    pub name: Option<String>,
+}
@@ -0,0 +1,20 @@
+//! Request and response definition for
+//! <https://www.developer.saxo/openapi/referencedocs/ref/v1/currencies>
+
+use crate::{saxo_request, saxo_response_list};
+
+use serde::Deserialize;
+
+saxo_request! {"ref/v1/currencies/"}
+saxo_response_list! {Currency}
+
+#[derive(Deserialize, Debug, Default, Clone, PartialEq)]
+#[serde(rename_all = "PascalCase")]
+pub struct Currency {
+    /// ISO 4217 code, e.g. `EUR`
+    pub currency_code: Option<String>,
+    /// Number of decimals of amounts in the currency
+    pub decimals: Option<i32>,
+    pub name: Option<String>,
+    pub symbol: Option<String>,
+}
@@ -1,3 +1,4 @@
+use crate::messages::common::TimeSpan;
 use crate::{saxo_request_odata, saxo_response_odata};
 
 use chrono::{DateTime, Utc};
@@ -14,19 +15,43 @@ saxo_response_odata! {
     country_code: String,
     currency: String,
     exchange_id: String,
-    //exchange_sessions: ExchangeSession, TODO: Fix
+    exchange_sessions: Vec<ExchangeSession>,
     iso_mic: String,
     name: String,
     operating_mic: String,
     price_source_name: String,
     time_zone: i32,
     time_zone_abbreviation: String,
-    time_zone_id: String
-    // TODO
-    //time_zone_offset: TimeSpan,
+    time_zone_id: String,
+    time_zone_offset: TimeSpan
 }
 
-#[derive(Deserialize, Debug, Default, PartialEq)]
+impl ResponseData {
+    /// Session of the exchange at `time`, if it is within the sessions sent by the server.
+    #[must_use]
+    pub fn session_at(&self, time: DateTime<Utc>) -> Option<&ExchangeSession> {
+        session_at(self.exchange_sessions.as_deref().unwrap_or_default(), time)
+    }
+
+    /// Whether the exchange is open at `time`, which is always the case for exchanges open all
+    /// day. Times outside the sessions sent by the server are considered closed.
+    #[must_use]
+    pub fn is_open_at(&self, time: DateTime<Utc>) -> bool {
+        self.all_day == Some(true)
+            || self
+                .session_at(time)
+                .and_then(|session| session.state)
+                .is_some_and(ExchangeSessionState::is_open)
+    }
+}
+
+/// Session among `sessions` which contains `time`.
+#[must_use]
+pub fn session_at(sessions: &[ExchangeSession], time: DateTime<Utc>) -> Option<&ExchangeSession> {
+    sessions.iter().find(|session| session.contains(time))
+}
+
+#[derive(Deserialize, Debug, Default, Clone, PartialEq)]
 #[serde(rename_all = "PascalCase")]
 pub struct ExchangeSession {
     pub end_time: Option<DateTime<Utc>>,
@@ -34,8 +59,19 @@ pub struct ExchangeSession {
     pub state: Option<ExchangeSessionState>,
 }
 
+impl ExchangeSession {
+    /// Whether `time` is within the session, which includes its start but not its end.
+    #[must_use]
+    pub fn contains(&self, time: DateTime<Utc>) -> bool {
+        matches!(
+            (self.start_time, self.end_time),
+            (Some(start), Some(end)) if start <= time && time < end
+        )
+    }
+}
+
 // TODO: Make own EnumString proc implementation?
-#[derive(EnumString, Deserialize, Debug, PartialEq)]
+#[derive(EnumString, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ExchangeSessionState {
     /// Participants place orders to buy or sell units at certain buying or selling prices. Orders collected during an auction are matched to form a contract
     Auction,
@@ -73,6 +109,22 @@ pub enum ExchangeSessionState {
     Undefined,
 }
 
+impl ExchangeSessionState {
+    /// Whether orders can be placed and traded on the exchange, including auctions and
+    /// extended trading sessions.
+    #[must_use]
+    pub fn is_open(self) -> bool {
+        !matches!(
+            self,
+            ExchangeSessionState::Break
+                | ExchangeSessionState::Closed
+                | ExchangeSessionState::Halt
+                | ExchangeSessionState::Suspended
+                | ExchangeSessionState::Undefined
+        )
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -115,4 +167,66 @@ mod tests {
         assert!(response_deserialized.is_ok());
         println!("{response_deserialized:?}");
     }
+
+    fn exchange() -> ResponseData {
+        let response = json!({
+            "AllDay": false,
+            "ExchangeId": "NYSE",
+            "ExchangeSessions": [
+                {
+                    "EndTime": "2023-06-01T13:30:00.000000Z",
+                    "StartTime": "2023-06-01T08:00:00.000000Z",
+                    "State": "PreTrading"
+                },
+                {
+                    "EndTime": "2023-06-01T20:00:00.000000Z",
+                    "StartTime": "2023-06-01T13:30:00.000000Z",
+                    "State": "AutomatedTrading"
+                },
+                {
+                    "EndTime": "2023-06-02T08:00:00.000000Z",
+                    "StartTime": "2023-06-01T20:00:00.000000Z",
+                    "State": "Closed"
+                }
+            ],
+            "TimeZoneId": "America/New_York",
+            "TimeZoneOffset": "-04:00:00"
+        });
+
+        serde_json::from_value(response).unwrap()
+    }
+
+    #[test]
+    fn test_serde_exchange_sessions() {
+        let exchange = exchange();
+
+        assert_eq!(exchange.exchange_sessions.as_ref().unwrap().len(), 3);
+        assert_eq!(
+            exchange.time_zone_offset,
+            Some(TimeSpan(chrono::Duration::hours(-4)))
+        );
+    }
+
+    #[test]
+    fn test_is_open_at() {
+        let exchange = exchange();
+        let time = |time: &str| time.parse::<DateTime<Utc>>().unwrap();
+
+        assert!(exchange.is_open_at(time("2023-06-01T09:00:00Z")));
+        assert!(exchange.is_open_at(time("2023-06-01T13:30:00Z")));
+        assert!(!exchange.is_open_at(time("2023-06-01T20:00:00Z")));
+        assert!(!exchange.is_open_at(time("2023-06-03T12:00:00Z")));
+        assert_eq!(
+            exchange
+                .session_at(time("2023-06-01T19:59:59Z"))
+                .and_then(|session| session.state),
+            Some(ExchangeSessionState::AutomatedTrading)
+        );
+
+        let all_day = ResponseData {
+            all_day: Some(true),
+            ..Default::default()
+        };
+        assert!(all_day.is_open_at(time("2023-06-03T12:00:00Z")));
+    }
 }
@@ -0,0 +1,297 @@
+//! Request and response definition for
+//! <https://www.developer.saxo/openapi/referencedocs/ref/v1/instruments>
+
+use crate::messages::common::AssetType;
+use crate::{saxo_request, saxo_response_list};
+
+use serde::Deserialize;
+
+// Instrument search with the `Keywords`, `AssetTypes` and `ExchangeId` query parameters. Paged
+// with `$top` and `$skip`.
+saxo_request! {"ref/v1/instruments/"}
+saxo_response_list! {Instrument}
+
+/// Instrument found by a search, identified by its UIC and asset type.
+#[derive(Deserialize, Debug, Default, Clone, PartialEq)]
+#[serde(rename_all = "PascalCase")]
+pub struct Instrument {
+    pub asset_type: Option<AssetType>,
+    pub currency_code: Option<String>,
+    pub description: Option<String>,
+    pub exchange_id: Option<String>,
+    pub group_id: Option<i32>,
+    /// UIC of the instrument
+    pub identifier: Option<i32>,
+    pub issuer_country: Option<String>,
+    pub primary_listing: Option<i32>,
+    pub summary_type: Option<String>,
+    pub symbol: Option<String>,
+    /// Other asset types the instrument can be traded as, e.g. `CfdOnStock` for a stock
+    #[serde(default)]
+    pub tradable_as: Vec<AssetType>,
+}
+
+/// Details of a single instrument with `{Uic}/{AssetType}` and the `FieldGroups` query
+/// parameter.
+pub mod details {
+    use super::super::exchanges::{self, ExchangeSession, ExchangeSessionState};
+    use crate::messages::common::{AssetType, DisplayAndFormat, FieldGroups};
+    use crate::messages::portfolio::orders::OrderType;
+    use crate::{saxo_request, saxo_response};
+
+    use chrono::{DateTime, Utc};
+    use serde::{Deserialize, Serialize};
+    use strum::AsRefStr;
+
+    saxo_request! {"ref/v1/instruments/details/"}
+    saxo_response! {
+        amount_decimals: i32,
+        asset_type: AssetType,
+        currency_code: String,
+        default_amount: f64,
+        description: String,
+        exchange: Exchange,
+        format: DisplayAndFormat,
+        group_id: i32,
+        is_tradable: bool,
+        lot_size: f64,
+        minimum_trade_size: f64,
+        order_distances: OrderDistances,
+        price_currency: String,
+        supported_order_types: Vec<OrderType>,
+        symbol: String,
+        tick_size: f64,
+        tick_size_scheme: TickSizeScheme,
+        trade_amount_type: String,
+        trading_sessions: TradingSessions,
+        uic: i32
+    }
+
+    #[derive(Serialize, AsRefStr, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+    pub enum FieldGroup {
+        OrderSetting,
+        SupportedOrderTypeSettings,
+        TradingSessions,
+    }
+
+    impl FieldGroup {
+        /// Order settings along with the trading sessions of the instrument.
+        #[must_use]
+        pub fn defaults() -> FieldGroups<FieldGroup> {
+            [FieldGroup::OrderSetting, FieldGroup::TradingSessions]
+                .into_iter()
+                .collect()
+        }
+    }
+
+    #[derive(Deserialize, Debug, Default, Clone, PartialEq)]
+    #[serde(rename_all = "PascalCase")]
+    pub struct Exchange {
+        pub country_code: Option<String>,
+        pub exchange_id: Option<String>,
+        pub name: Option<String>,
+        pub time_zone_id: Option<String>,
+    }
+
+    /// Default distances from the market of the related orders of a new order.
+    #[derive(Deserialize, Debug, Default, Clone, PartialEq)]
+    #[serde(rename_all = "PascalCase")]
+    pub struct OrderDistances {
+        pub entry_default_distance: Option<f64>,
+        pub entry_default_distance_type: Option<DistanceType>,
+        pub limit_default_distance: Option<f64>,
+        pub limit_default_distance_type: Option<DistanceType>,
+        pub stop_limit_default_distance: Option<f64>,
+        pub stop_limit_default_distance_type: Option<DistanceType>,
+        pub stop_loss_default_distance: Option<f64>,
+        pub stop_loss_default_distance_type: Option<DistanceType>,
+        pub stop_loss_default_enabled: Option<bool>,
+        pub stop_loss_default_order_type: Option<OrderType>,
+        pub take_profit_default_distance: Option<f64>,
+        pub take_profit_default_distance_type: Option<DistanceType>,
+        pub take_profit_default_enabled: Option<bool>,
+    }
+
+    #[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+    pub enum DistanceType {
+        /// Distance in pips
+        Pips,
+        /// Distance in percent of the market price
+        Percentage,
+        /// Distance in the price currency
+        Price,
+        /// Distance type not known to this crate
+        #[serde(other)]
+        Unknown,
+    }
+
+    /// Tick sizes depending on the price, used instead of the fixed tick size of the
+    /// instrument.
+    #[derive(Deserialize, Debug, Default, Clone, PartialEq)]
+    #[serde(rename_all = "PascalCase")]
+    pub struct TickSizeScheme {
+        pub default_tick_size: Option<f64>,
+        #[serde(default)]
+        pub elements: Vec<TickSizeElement>,
+    }
+
+    /// Tick size of prices up to and including `high_price`.
+    #[derive(Deserialize, Debug, Default, Clone, Copy, PartialEq)]
+    #[serde(rename_all = "PascalCase")]
+    pub struct TickSizeElement {
+        pub high_price: f64,
+        pub tick_size: f64,
+    }
+
+    impl TickSizeScheme {
+        /// Tick size at `price`, which is the default tick size above the highest price of
+        /// the scheme.
+        #[must_use]
+        pub fn tick_size(&self, price: f64) -> Option<f64> {
+            self.elements
+                .iter()
+                .filter(|element| price <= element.high_price)
+                .min_by(|a, b| a.high_price.total_cmp(&b.high_price))
+                .map(|element| element.tick_size)
+                .or(self.default_tick_size)
+        }
+    }
+
+    #[derive(Deserialize, Debug, Default, PartialEq)]
+    #[serde(rename_all = "PascalCase")]
+    pub struct TradingSessions {
+        #[serde(default)]
+        pub sessions: Vec<ExchangeSession>,
+    }
+
+    impl TradingSessions {
+        /// Whether the instrument can be traded at `time`, see
+        /// [`exchanges::ResponseData::is_open_at`].
+        #[must_use]
+        pub fn is_open_at(&self, time: DateTime<Utc>) -> bool {
+            exchanges::session_at(&self.sessions, time)
+                .and_then(|session| session.state)
+                .is_some_and(ExchangeSessionState::is_open)
+        }
+    }
+
+    impl Response {
+        /// Tick size at `price`, from the tick size scheme if the instrument has one.
+        #[must_use]
+        pub fn tick_size_at(&self, price: f64) -> Option<f64> {
+            self.tick_size_scheme
+                .as_ref()
+                .and_then(|scheme| scheme.tick_size(price))
+                .or(self.tick_size)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use super::super::exchanges::ExchangeSessionState;
+    use crate::messages::portfolio::orders::OrderType;
+    use crate::SaxoResponseOData;
+    use serde_json::json;
+
+    #[test]
+    fn test_serde_instruments() {
+        let response = json!({
+            "Data": [
+                {
+                    "AssetType": "Stock",
+                    "CurrencyCode": "USD",
+                    "Description": "Apple Inc.",
+                    "ExchangeId": "NASDAQ",
+                    "GroupId": 0,
+                    "Identifier": 211,
+                    "IssuerCountry": "US",
+                    "PrimaryListing": 211,
+                    "SummaryType": "Instrument",
+                    "Symbol": "AAPL:xnas",
+                    "TradableAs": ["Stock", "CfdOnStock"]
+                }
+            ],
+            "__next": "/openapi/ref/v1/instruments/?$top=1&$skip=1&Keywords=AAPL&AssetTypes=Stock"
+        });
+
+        let response = serde_json::from_value::<Response>(response).unwrap();
+
+        assert_eq!(response.data[0].identifier, Some(211));
+        assert_eq!(
+            response.data[0].tradable_as,
+            vec![AssetType::Stock, AssetType::CfdOnStock]
+        );
+        assert_eq!(
+            response.next().unwrap().argument().to_string(),
+            "?%24top=1&%24skip=1&Keywords=AAPL&AssetTypes=Stock"
+        );
+    }
+
+    #[test]
+    fn test_serde_instrument_details() {
+        let response = json!({
+            "AssetType": "Stock",
+            "Exchange": { "ExchangeId": "NASDAQ", "TimeZoneId": "America/New_York" },
+            "OrderDistances": {
+                "EntryDefaultDistance": 0.5,
+                "EntryDefaultDistanceType": "Percentage",
+                "StopLossDefaultEnabled": false,
+                "StopLossDefaultOrderType": "Stop"
+            },
+            "SupportedOrderTypes": ["Market", "Limit", "StopIfTraded"],
+            "TickSize": 0.01,
+            "TickSizeScheme": {
+                "DefaultTickSize": 0.05,
+                "Elements": [
+                    { "HighPrice": 0.9999, "TickSize": 0.0001 },
+                    { "HighPrice": 100.0, "TickSize": 0.01 }
+                ]
+            },
+            "TradingSessions": {
+                "Sessions": [
+                    {
+                        "EndTime": "2023-06-01T13:30:00.000000Z",
+                        "StartTime": "2023-06-01T08:00:00.000000Z",
+                        "State": "PreMarket"
+                    },
+                    {
+                        "EndTime": "2023-06-01T20:00:00.000000Z",
+                        "StartTime": "2023-06-01T13:30:00.000000Z",
+                        "State": "AutomatedTrading"
+                    }
+                ]
+            },
+            "Uic": 211
+        });
+
+        let response = serde_json::from_value::<details::Response>(response).unwrap();
+
+        assert_eq!(
+            response.supported_order_types.as_deref(),
+            Some(&[OrderType::Market, OrderType::Limit, OrderType::StopIfTraded][..])
+        );
+        assert_eq!(
+            response
+                .order_distances
+                .as_ref()
+                .unwrap()
+                .entry_default_distance_type,
+            Some(details::DistanceType::Percentage)
+        );
+        assert_eq!(
+            response.trading_sessions.as_ref().unwrap().sessions[1].state,
+            Some(ExchangeSessionState::AutomatedTrading)
+        );
+        assert!(response
+            .trading_sessions
+            .as_ref()
+            .unwrap()
+            .is_open_at("2023-06-01T14:00:00Z".parse().unwrap()));
+        assert_eq!(response.tick_size_at(0.5), Some(0.0001));
+        assert_eq!(response.tick_size_at(42.0), Some(0.01));
+        assert_eq!(response.tick_size_at(250.0), Some(0.05));
+    }
+}
@@ -0,0 +1,18 @@
+//! Request and response definition for
+//! <https://www.developer.saxo/openapi/referencedocs/ref/v1/languages>
+
+use crate::{saxo_request, saxo_response_list};
+
+use serde::Deserialize;
+
+saxo_request! {"ref/v1/languages/"}
+saxo_response_list! {Language}
+
+#[derive(Deserialize, Debug, Default, Clone, PartialEq)]
+#[serde(rename_all = "PascalCase")]
+pub struct Language {
+    /// ISO 639-1 code, e.g. `da`
+    pub language_code: Option<String>,
+    pub language_name: Option<String>,
+    pub native_name: Option<String>,
+}
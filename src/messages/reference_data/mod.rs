@@ -1 +1,7 @@
+pub mod countries;
+pub mod cultures;
+pub mod currencies;
 pub mod exchanges;
+pub mod instruments;
+pub mod languages;
+pub mod timezones;
@@ -0,0 +1,19 @@
+//! Request and response definition for
+//! <https://www.developer.saxo/openapi/referencedocs/ref/v1/timezones>
+
+use crate::{saxo_request, saxo_response_list};
+
+use serde::Deserialize;
+
+saxo_request! {"ref/v1/timezones/"}
+saxo_response_list! {TimeZone}
+
+#[derive(Deserialize, Debug, Default, Clone, PartialEq)]
+#[serde(rename_all = "PascalCase")]
+pub struct TimeZone {
+    pub display_name: Option<String>,
+    /// Id of the time zone, as in the `TimeZoneId` of a user
+    pub time_zone_id: Option<i32>,
+    /// IANA name, e.g. `Europe/Copenhagen`
+    pub zone_name: Option<String>,
+}
@@ -0,0 +1,230 @@
+//! Request and response definition for
+//! <https://www.developer.saxo/openapi/referencedocs/trade/v1/infoprices>
+
+use crate::messages::common::{AssetType, DisplayAndFormat, FieldGroups};
+use crate::streaming::subscription::Subscription;
+use crate::{saxo_request, saxo_response};
+
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use strum::{AsRefStr, EnumString};
+
+/// Path of the info price subscriptions end point.
+pub const SUBSCRIPTIONS: &str = "trade/v1/infoprices/subscriptions";
+
+// Price of a single instrument with the `Uic`, `AssetType`, `Amount` and `FieldGroups` query
+// parameters
+saxo_request! {"trade/v1/infoprices/"}
+saxo_response! {
+    asset_type: AssetType,
+    display_and_format: DisplayAndFormat,
+    last_updated: String,
+    price_info: PriceInfo,
+    price_info_details: PriceInfoDetails,
+    price_source: String,
+    quote: Quote,
+    uic: i32
+}
+
+#[derive(Serialize, AsRefStr, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum FieldGroup {
+    DisplayAndFormat,
+    PriceInfo,
+    PriceInfoDetails,
+    Quote,
+}
+
+impl FieldGroup {
+    /// Quote along with the price info of the trading day.
+    #[must_use]
+    pub fn defaults() -> FieldGroups<FieldGroup> {
+        [
+            FieldGroup::PriceInfo,
+            FieldGroup::PriceInfoDetails,
+            FieldGroup::Quote,
+        ]
+        .into_iter()
+        .collect()
+    }
+}
+
+/// Best bid and ask prices of an instrument.
+#[derive(Deserialize, Debug, Default, Clone, PartialEq)]
+#[serde(rename_all = "PascalCase")]
+pub struct Quote {
+    pub amount: Option<f64>,
+    pub ask: Option<f64>,
+    pub ask_size: Option<f64>,
+    pub bid: Option<f64>,
+    pub bid_size: Option<f64>,
+    pub delayed_by_minutes: Option<i32>,
+    pub error_code: Option<String>,
+    pub market_state: Option<MarketState>,
+    pub mid: Option<f64>,
+    pub price_source: Option<String>,
+    pub price_source_type: Option<String>,
+    pub price_type_ask: Option<PriceType>,
+    pub price_type_bid: Option<PriceType>,
+}
+
+/// High, low and change of the price during the trading day.
+#[derive(Deserialize, Debug, Default, Clone, PartialEq)]
+#[serde(rename_all = "PascalCase")]
+pub struct PriceInfo {
+    pub high: Option<f64>,
+    pub low: Option<f64>,
+    pub net_change: Option<f64>,
+    pub percent_change: Option<f64>,
+}
+
+/// Trade and session prices of exchange traded instruments.
+#[derive(Deserialize, Debug, Default, Clone, PartialEq)]
+#[serde(rename_all = "PascalCase")]
+pub struct PriceInfoDetails {
+    pub ask_size: Option<f64>,
+    pub bid_size: Option<f64>,
+    pub last_close: Option<f64>,
+    pub last_traded: Option<f64>,
+    pub last_traded_size: Option<f64>,
+    pub open: Option<f64>,
+    pub volume: Option<f64>,
+}
+
+#[derive(EnumString, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MarketState {
+    /// Market is closed
+    Closed,
+    /// Auction before the market opens
+    OpeningAuction,
+    /// Market is open for continuous trading
+    Open,
+    /// Auction before the market closes
+    ClosingAuction,
+    /// Auction during the trading day
+    IntraDayAuction,
+    /// Trading only at the last traded price, after the close
+    TradingAtLast,
+    /// Market state not known to this crate
+    #[serde(other)]
+    Unknown,
+}
+
+#[derive(EnumString, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PriceType {
+    /// Price is only indicative and cannot be traded on
+    Indicative,
+    /// No price is available
+    NoAccess,
+    /// There is no market for the instrument
+    NoMarket,
+    /// Price is indicative and no longer recent
+    OldIndicative,
+    /// Price is waiting for a response from the exchange
+    Pending,
+    /// Price can be traded on
+    Tradable,
+    /// Price type not known to this crate
+    #[serde(other)]
+    Unknown,
+}
+
+/// Prices of several instruments of the same asset type, with the `Uics`, `AssetType`, `Amount`
+/// and `FieldGroups` query parameters.
+pub mod list {
+    use crate::{saxo_request, saxo_response_list};
+
+    saxo_request! {"trade/v1/infoprices/list"}
+    saxo_response_list! {super::Response}
+}
+
+/// Subscription to the prices of several instruments of the same asset type. The `Data`
+/// elements of the snapshot and deltas are identified by their `Uic`.
+#[must_use]
+pub fn subscription(
+    reference_id: &str,
+    uics: &[i32],
+    asset_type: &AssetType,
+    field_groups: &FieldGroups<FieldGroup>,
+) -> Subscription {
+    let uics = uics
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join(",");
+    let arguments = json!({
+        "AssetType": asset_type,
+        "FieldGroups": field_groups,
+        "Uics": uics
+    });
+
+    Subscription::new(SUBSCRIPTIONS, reference_id, arguments)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_serde_info_prices() {
+        let response = json!({
+            "Data": [
+                {
+                    "AssetType": "FxSpot",
+                    "LastUpdated": "2023-06-01T08:00:00.000000Z",
+                    "PriceInfo": { "High": 1.08, "Low": 1.06, "NetChange": 0.01 },
+                    "PriceSource": "SBFX",
+                    "Quote": {
+                        "Amount": 100_000,
+                        "Ask": 1.0705,
+                        "Bid": 1.0703,
+                        "DelayedByMinutes": 0,
+                        "MarketState": "Open",
+                        "Mid": 1.0704,
+                        "PriceTypeAsk": "Tradable",
+                        "PriceTypeBid": "Tradable"
+                    },
+                    "Uic": 21
+                },
+                {
+                    "AssetType": "FxSpot",
+                    "Quote": { "MarketState": "Suspended", "PriceTypeAsk": "NoMarket" },
+                    "Uic": 22
+                }
+            ]
+        });
+
+        let response = serde_json::from_value::<list::Response>(response).unwrap();
+
+        let quote = response.data[0].quote.as_ref().unwrap();
+        assert_eq!(quote.mid, Some(1.0704));
+        assert_eq!(quote.market_state, Some(MarketState::Open));
+        assert_eq!(quote.price_type_bid, Some(PriceType::Tradable));
+        assert_eq!(
+            response.data[0].price_info.as_ref().unwrap().net_change,
+            Some(0.01)
+        );
+
+        let quote = response.data[1].quote.as_ref().unwrap();
+        assert_eq!(quote.market_state, Some(MarketState::Unknown));
+        assert_eq!(quote.price_type_ask, Some(PriceType::NoMarket));
+    }
+
+    #[test]
+    fn test_subscription() {
+        let field_groups = [FieldGroup::Quote].into_iter().collect();
+        let subscription = subscription("prices", &[21, 22], &AssetType::FxSpot, &field_groups);
+
+        assert_eq!(
+            subscription.arguments,
+            json!({ "AssetType": "FxSpot", "FieldGroups": ["Quote"], "Uics": "21,22" })
+        );
+    }
+
+    #[test]
+    fn test_field_groups() {
+        assert_eq!(
+            FieldGroup::defaults().to_string(),
+            "PriceInfo,PriceInfoDetails,Quote"
+        );
+    }
+}
@@ -0,0 +1,48 @@
+pub mod infoprices;
+pub mod multileg;
+pub mod orders;
+pub mod precheck;
+pub mod prices;
+pub mod related_orders;
+
+use crate::messages::portfolio::orders::OrderType;
+
+/// Invalid combination of orders or legs, detected by the builders before the orders are placed.
+#[derive(thiserror::Error, Debug, PartialEq, Eq)]
+pub enum OrderError {
+    #[error("No take profit or stop loss is attached to the entry order")]
+    NoRelatedOrders,
+
+    #[error("Both a stop loss and a trailing stop loss are attached to the entry order")]
+    ConflictingStopLoss,
+
+    #[error("{0:?} orders cannot have related orders")]
+    InvalidEntryType(OrderType),
+
+    #[error("Take profit is not on the profitable side of the entry price")]
+    TakeProfitNotProfitable,
+
+    #[error("Stop loss is not on the losing side of the entry price or take profit")]
+    StopLossNotProtective,
+
+    #[error("Trailing stop distance and step must be positive")]
+    InvalidTrailingStop,
+
+    #[error("One-cancels-other orders must be for the same account and instrument")]
+    MismatchedInstrument,
+
+    #[error("Market orders cannot be part of one-cancels-other orders")]
+    MarketOrderInOco,
+
+    #[error("Multi-leg orders must have at least two legs")]
+    TooFewLegs,
+
+    #[error("Legs of a multi-leg order must have the same option root")]
+    MixedOptionRoots,
+
+    #[error("Legs of a multi-leg order must be for different instruments")]
+    DuplicateLeg,
+
+    #[error("Leg ratios must be positive")]
+    InvalidRatio,
+}
@@ -0,0 +1,319 @@
+//! Request and response definition for
+//! <https://www.developer.saxo/openapi/referencedocs/trade/v2/orders/multileg>
+
+use super::orders::OrderId;
+use super::OrderError;
+use crate::messages::common::AssetType;
+use crate::messages::portfolio::orders::{BuySell, Duration, OrderDuration, OrderType, PutCall};
+
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+/// Path of the multi-leg order end point.
+pub const MULTILEG: &str = "trade/v2/orders/multileg";
+
+/// Path of the multi-leg order precheck end point.
+pub const MULTILEG_PRECHECK: &str = "trade/v2/orders/multileg/precheck";
+
+/// Listed option contract, e.g. as found in the option space of an option root. This is
+/// metadata of the caller: it isn't sent to the server, nor checked against the UIC of a leg.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct OptionContract {
+    pub option_root_id: i32,
+    pub strike: f64,
+    pub expiry: NaiveDate,
+    pub put_call: PutCall,
+}
+
+/// Leg of an option strategy, buying or selling `ratio` contracts per unit of the strategy. The
+/// instrument is identified by `uic` and `asset_type` only.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OptionLeg {
+    pub uic: i32,
+    pub asset_type: AssetType,
+    pub contract: OptionContract,
+    pub buy_sell: BuySell,
+    pub ratio: u32,
+}
+
+impl OptionLeg {
+    /// Stock option leg of the contract with the given UIC.
+    #[must_use]
+    pub fn new(uic: i32, contract: OptionContract, buy_sell: BuySell, ratio: u32) -> Self {
+        OptionLeg {
+            uic,
+            asset_type: AssetType::StockOption,
+            contract,
+            buy_sell,
+            ratio,
+        }
+    }
+
+    /// Sets the asset type of the contract, e.g. `StockIndexOption`.
+    #[must_use]
+    pub fn asset_type(mut self, asset_type: AssetType) -> Self {
+        self.asset_type = asset_type;
+        self
+    }
+}
+
+/// Body of the request placing a multi-leg order, where all legs are filled together at the
+/// net price of the strategy.
+#[derive(Serialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "PascalCase")]
+pub struct MultiLegOrder {
+    pub account_key: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub external_reference: Option<String>,
+    pub legs: Vec<Leg>,
+    pub manual_order: bool,
+    pub order_duration: Duration,
+    /// Net price of the strategy, negative for a credit
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub order_price: Option<f64>,
+    pub order_type: OrderType,
+}
+
+/// Leg of a multi-leg order as sent to the end point. The contract is kept as local metadata.
+#[derive(Serialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "PascalCase")]
+pub struct Leg {
+    pub amount: f64,
+    pub asset_type: AssetType,
+    pub buy_sell: BuySell,
+    #[serde(skip)]
+    pub contract: OptionContract,
+    pub uic: i32,
+}
+
+impl MultiLegOrder {
+    /// Starts building a strategy of `amount` units filled at the best available net price.
+    #[must_use]
+    pub fn market(account_key: &str, amount: f64) -> MultiLegOrderBuilder {
+        MultiLegOrderBuilder::new(account_key, amount, OrderType::Market, None)
+    }
+
+    /// Starts building a strategy of `amount` units filled at the net `price` or better.
+    #[must_use]
+    pub fn limit(account_key: &str, amount: f64, price: f64) -> MultiLegOrderBuilder {
+        MultiLegOrderBuilder::new(account_key, amount, OrderType::Limit, Some(price))
+    }
+}
+
+/// Adds the legs of a multi-leg order, validating them on [`MultiLegOrderBuilder::build`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct MultiLegOrderBuilder {
+    account_key: String,
+    amount: f64,
+    order_type: OrderType,
+    order_price: Option<f64>,
+    order_duration: Duration,
+    external_reference: Option<String>,
+    manual_order: bool,
+    legs: Vec<OptionLeg>,
+}
+
+impl MultiLegOrderBuilder {
+    fn new(
+        account_key: &str,
+        amount: f64,
+        order_type: OrderType,
+        order_price: Option<f64>,
+    ) -> Self {
+        MultiLegOrderBuilder {
+            account_key: account_key.to_owned(),
+            amount,
+            order_type,
+            order_price,
+            order_duration: OrderDuration::DayOrder.into(),
+            external_reference: None,
+            manual_order: false,
+            legs: Vec::new(),
+        }
+    }
+
+    #[must_use]
+    pub fn leg(mut self, leg: OptionLeg) -> Self {
+        self.legs.push(leg);
+        self
+    }
+
+    /// Sets how long the order is valid, a day order by default.
+    #[must_use]
+    pub fn duration(mut self, duration: impl Into<Duration>) -> Self {
+        self.order_duration = duration.into();
+        self
+    }
+
+    /// Sets a reference of the order in the calling application.
+    #[must_use]
+    pub fn external_reference(mut self, external_reference: &str) -> Self {
+        self.external_reference = Some(external_reference.to_owned());
+        self
+    }
+
+    /// Marks the order as placed by a person rather than by an automated system.
+    #[must_use]
+    pub fn manual(mut self) -> Self {
+        self.manual_order = true;
+        self
+    }
+
+    /// Checks the legs, which must be at least two distinct instruments with a non-zero ratio.
+    /// The option roots are compared on the contracts given by the caller.
+    pub fn build(self) -> Result<MultiLegOrder, OrderError> {
+        if self.legs.len() < 2 {
+            return Err(OrderError::TooFewLegs);
+        }
+        if self.legs.iter().any(|leg| leg.ratio == 0) {
+            return Err(OrderError::InvalidRatio);
+        }
+        let option_root_id = self.legs[0].contract.option_root_id;
+        if self
+            .legs
+            .iter()
+            .any(|leg| leg.contract.option_root_id != option_root_id)
+        {
+            return Err(OrderError::MixedOptionRoots);
+        }
+        for (i, leg) in self.legs.iter().enumerate() {
+            if self.legs[..i]
+                .iter()
+                .any(|other| other.uic == leg.uic && other.asset_type == leg.asset_type)
+            {
+                return Err(OrderError::DuplicateLeg);
+            }
+        }
+
+        let amount = self.amount;
+        Ok(MultiLegOrder {
+            account_key: self.account_key,
+            external_reference: self.external_reference,
+            legs: self
+                .legs
+                .into_iter()
+                .map(|leg| Leg {
+                    amount: amount * f64::from(leg.ratio),
+                    asset_type: leg.asset_type,
+                    buy_sell: leg.buy_sell,
+                    contract: leg.contract,
+                    uic: leg.uic,
+                })
+                .collect(),
+            manual_order: self.manual_order,
+            order_duration: self.order_duration,
+            order_price: self.order_price,
+            order_type: self.order_type,
+        })
+    }
+}
+
+/// Id of a placed multi-leg order, along with the ids of the orders of its legs.
+#[derive(Deserialize, Debug, Default, Clone, PartialEq, Eq)]
+#[serde(rename_all = "PascalCase")]
+pub struct MultiLegOrderId {
+    #[serde(default)]
+    pub multi_leg_order_id: String,
+    #[serde(default)]
+    pub orders: Vec<OrderId>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use serde_json::json;
+
+    fn call(uic: i32, strike: f64) -> OptionLeg {
+        let contract = OptionContract {
+            option_root_id: 19,
+            strike,
+            expiry: NaiveDate::from_ymd_opt(2023, 6, 16).unwrap(),
+            put_call: PutCall::Call,
+        };
+        OptionLeg::new(uic, contract, BuySell::Buy, 1)
+    }
+
+    #[test]
+    fn test_serialize_multi_leg_order() {
+        let mut short_call = call(2, 190.0);
+        short_call.buy_sell = BuySell::Sell;
+        short_call.ratio = 2;
+        let order = MultiLegOrder::limit("account", 5.0, 1.25)
+            .leg(call(1, 180.0))
+            .leg(short_call)
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            serde_json::to_value(&order).unwrap(),
+            json!({
+                "AccountKey": "account",
+                "Legs": [
+                    { "Amount": 5.0, "AssetType": "StockOption", "BuySell": "Buy", "Uic": 1 },
+                    { "Amount": 10.0, "AssetType": "StockOption", "BuySell": "Sell", "Uic": 2 }
+                ],
+                "ManualOrder": false,
+                "OrderDuration": { "DurationType": "DayOrder" },
+                "OrderPrice": 1.25,
+                "OrderType": "Limit"
+            })
+        );
+    }
+
+    #[test]
+    fn test_invalid_multi_leg_order() {
+        let order = || MultiLegOrder::market("account", 1.0);
+
+        assert_eq!(
+            order().leg(call(1, 180.0)).build(),
+            Err(OrderError::TooFewLegs)
+        );
+        assert_eq!(
+            order()
+                .leg(call(1, 180.0))
+                .leg(call(2, 190.0).asset_type(AssetType::StockIndexOption))
+                .leg(call(3, 200.0))
+                .build()
+                .map(|order| order.legs.len()),
+            Ok(3)
+        );
+
+        let mut leg = call(2, 190.0);
+        leg.ratio = 0;
+        assert_eq!(
+            order().leg(call(1, 180.0)).leg(leg).build(),
+            Err(OrderError::InvalidRatio)
+        );
+
+        let mut leg = call(2, 190.0);
+        leg.contract.option_root_id = 20;
+        assert_eq!(
+            order().leg(call(1, 180.0)).leg(leg).build(),
+            Err(OrderError::MixedOptionRoots)
+        );
+
+        assert_eq!(
+            order().leg(call(1, 180.0)).leg(call(1, 190.0)).build(),
+            Err(OrderError::DuplicateLeg)
+        );
+        assert!(order()
+            .leg(call(1, 180.0))
+            .leg(call(1, 180.0).asset_type(AssetType::StockIndexOption))
+            .build()
+            .is_ok());
+    }
+
+    #[test]
+    fn test_serde_multi_leg_order_id() {
+        let response = json!({
+            "MultiLegOrderId": "5001",
+            "Orders": [{ "OrderId": "1" }, { "OrderId": "2" }]
+        });
+
+        let order_id = serde_json::from_value::<MultiLegOrderId>(response).unwrap();
+
+        assert_eq!(order_id.multi_leg_order_id, "5001");
+        assert_eq!(order_id.orders[1].order_id, "2");
+    }
+}
@@ -0,0 +1,373 @@
+//! Request and response definition for
+//! <https://www.developer.saxo/openapi/referencedocs/trade/v2/orders>
+
+use crate::messages::common::AssetType;
+use crate::messages::portfolio::orders::{BuySell, Duration, OrderDuration, OrderType};
+
+use serde::{Deserialize, Serialize};
+
+/// Path of the order end point, shared by placement, modification and cancellation.
+pub const ORDERS: &str = "trade/v2/orders";
+
+/// Body of the request placing a new order.
+#[derive(Serialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "PascalCase")]
+pub struct Order {
+    pub account_key: String,
+    pub amount: f64,
+    pub asset_type: AssetType,
+    pub buy_sell: BuySell,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub external_reference: Option<String>,
+    pub manual_order: bool,
+    pub order_duration: Duration,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub order_price: Option<f64>,
+    pub order_type: OrderType,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stop_limit_price: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub trailing_stop_distance_to_market: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub trailing_stop_step: Option<f64>,
+    pub uic: i32,
+}
+
+impl Order {
+    pub(crate) fn new(
+        account_key: &str,
+        uic: i32,
+        asset_type: AssetType,
+        buy_sell: BuySell,
+        amount: f64,
+        order_type: OrderType,
+    ) -> Self {
+        Order {
+            account_key: account_key.to_owned(),
+            amount,
+            asset_type,
+            buy_sell,
+            external_reference: None,
+            manual_order: false,
+            order_duration: OrderDuration::DayOrder.into(),
+            order_price: None,
+            order_type,
+            stop_limit_price: None,
+            trailing_stop_distance_to_market: None,
+            trailing_stop_step: None,
+            uic,
+        }
+    }
+
+    /// Order filled at the best available price.
+    #[must_use]
+    pub fn market(
+        account_key: &str,
+        uic: i32,
+        asset_type: AssetType,
+        buy_sell: BuySell,
+        amount: f64,
+    ) -> Self {
+        Self::new(
+            account_key,
+            uic,
+            asset_type,
+            buy_sell,
+            amount,
+            OrderType::Market,
+        )
+    }
+
+    /// Order filled at `price` or better.
+    #[must_use]
+    pub fn limit(
+        account_key: &str,
+        uic: i32,
+        asset_type: AssetType,
+        buy_sell: BuySell,
+        amount: f64,
+        price: f64,
+    ) -> Self {
+        Self::new(
+            account_key,
+            uic,
+            asset_type,
+            buy_sell,
+            amount,
+            OrderType::Limit,
+        )
+        .price(price)
+    }
+
+    /// Market order triggered once the market reaches `price`.
+    #[must_use]
+    pub fn stop(
+        account_key: &str,
+        uic: i32,
+        asset_type: AssetType,
+        buy_sell: BuySell,
+        amount: f64,
+        price: f64,
+    ) -> Self {
+        Self::new(
+            account_key,
+            uic,
+            asset_type,
+            buy_sell,
+            amount,
+            OrderType::Stop,
+        )
+        .price(price)
+    }
+
+    /// Limit order at `limit_price`, triggered once the market reaches `stop_price`.
+    #[must_use]
+    pub fn stop_limit(
+        account_key: &str,
+        uic: i32,
+        asset_type: AssetType,
+        buy_sell: BuySell,
+        amount: f64,
+        stop_price: f64,
+        limit_price: f64,
+    ) -> Self {
+        let mut order = Self::new(
+            account_key,
+            uic,
+            asset_type,
+            buy_sell,
+            amount,
+            OrderType::StopLimit,
+        )
+        .price(stop_price);
+        order.stop_limit_price = Some(limit_price);
+        order
+    }
+
+    #[must_use]
+    pub(crate) fn price(mut self, price: f64) -> Self {
+        self.order_price = Some(price);
+        self
+    }
+
+    /// Sets how long the order is valid, a day order by default.
+    #[must_use]
+    pub fn duration(mut self, duration: impl Into<Duration>) -> Self {
+        self.order_duration = duration.into();
+        self
+    }
+
+    /// Sets a reference of the order in the calling application.
+    #[must_use]
+    pub fn external_reference(mut self, external_reference: &str) -> Self {
+        self.external_reference = Some(external_reference.to_owned());
+        self
+    }
+
+    /// Marks the order as placed by a person rather than by an automated system.
+    #[must_use]
+    pub fn manual(mut self) -> Self {
+        self.manual_order = true;
+        self
+    }
+}
+
+/// Body of the request modifying a working order. Fields left out keep their current value.
+#[derive(Serialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "PascalCase")]
+pub struct ModifyOrder {
+    pub account_key: String,
+    pub asset_type: AssetType,
+    pub order_id: String,
+    pub order_type: OrderType,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub amount: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub order_duration: Option<Duration>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub order_price: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stop_limit_price: Option<f64>,
+}
+
+impl ModifyOrder {
+    #[must_use]
+    pub fn new(
+        account_key: &str,
+        order_id: &str,
+        asset_type: AssetType,
+        order_type: OrderType,
+    ) -> Self {
+        ModifyOrder {
+            account_key: account_key.to_owned(),
+            asset_type,
+            order_id: order_id.to_owned(),
+            order_type,
+            amount: None,
+            order_duration: None,
+            order_price: None,
+            stop_limit_price: None,
+        }
+    }
+
+    #[must_use]
+    pub fn amount(mut self, amount: f64) -> Self {
+        self.amount = Some(amount);
+        self
+    }
+
+    #[must_use]
+    pub fn duration(mut self, duration: impl Into<Duration>) -> Self {
+        self.order_duration = Some(duration.into());
+        self
+    }
+
+    #[must_use]
+    pub fn price(mut self, price: f64) -> Self {
+        self.order_price = Some(price);
+        self
+    }
+
+    #[must_use]
+    pub fn stop_limit_price(mut self, stop_limit_price: f64) -> Self {
+        self.stop_limit_price = Some(stop_limit_price);
+        self
+    }
+}
+
+/// Id of a placed, modified or cancelled order, along with the ids of its related orders.
+/// The id is empty for one-cancels-other orders, which only consist of related orders.
+#[derive(Deserialize, Debug, Default, Clone, PartialEq, Eq)]
+#[serde(rename_all = "PascalCase")]
+pub struct OrderId {
+    #[serde(default)]
+    pub order_id: String,
+    #[serde(default)]
+    pub orders: Vec<OrderId>,
+}
+
+impl OrderId {
+    /// Ids of the order and all of its related orders.
+    #[must_use]
+    pub fn ids(&self) -> Vec<&str> {
+        let mut ids = Vec::new();
+        if !self.order_id.is_empty() {
+            ids.push(self.order_id.as_str());
+        }
+        ids.extend(self.orders.iter().flat_map(OrderId::ids));
+        ids
+    }
+}
+
+/// Ids of the cancelled orders.
+#[derive(Deserialize, Debug, Default, Clone, PartialEq, Eq)]
+#[serde(rename_all = "PascalCase")]
+pub struct CancelledOrders {
+    #[serde(default)]
+    pub orders: Vec<OrderId>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use serde_json::json;
+
+    #[test]
+    fn test_serialize_order() {
+        let order = Order::stop_limit(
+            "account",
+            21,
+            AssetType::FxSpot,
+            BuySell::Sell,
+            1000.0,
+            1.05,
+            1.04,
+        )
+        .duration(Duration {
+            duration_type: OrderDuration::GoodTillDate,
+            expiration_date_time: Some("2023-06-01T00:00:00".to_owned()),
+            expiration_date_contains_time: Some(true),
+        })
+        .external_reference("foo");
+
+        assert_eq!(
+            serde_json::to_value(&order).unwrap(),
+            json!({
+                "AccountKey": "account",
+                "Amount": 1000.0,
+                "AssetType": "FxSpot",
+                "BuySell": "Sell",
+                "ExternalReference": "foo",
+                "ManualOrder": false,
+                "OrderDuration": {
+                    "DurationType": "GoodTillDate",
+                    "ExpirationDateTime": "2023-06-01T00:00:00",
+                    "ExpirationDateContainsTime": true
+                },
+                "OrderPrice": 1.05,
+                "OrderType": "StopLimit",
+                "StopLimitPrice": 1.04,
+                "Uic": 21
+            })
+        );
+    }
+
+    #[test]
+    fn test_serialize_market_order() {
+        let order = Order::market("account", 21, AssetType::FxSpot, BuySell::Buy, 1000.0).manual();
+
+        assert_eq!(
+            serde_json::to_value(&order).unwrap(),
+            json!({
+                "AccountKey": "account",
+                "Amount": 1000.0,
+                "AssetType": "FxSpot",
+                "BuySell": "Buy",
+                "ManualOrder": true,
+                "OrderDuration": { "DurationType": "DayOrder" },
+                "OrderType": "Market",
+                "Uic": 21
+            })
+        );
+    }
+
+    #[test]
+    fn test_serialize_modify_order() {
+        let order =
+            ModifyOrder::new("account", "123", AssetType::FxSpot, OrderType::Limit).price(1.1);
+
+        assert_eq!(
+            serde_json::to_value(&order).unwrap(),
+            json!({
+                "AccountKey": "account",
+                "AssetType": "FxSpot",
+                "OrderId": "123",
+                "OrderType": "Limit",
+                "OrderPrice": 1.1
+            })
+        );
+    }
+
+    #[test]
+    fn test_serde_order_id() {
+        let response = json!({ "OrderId": "1", "Orders": [{ "OrderId": "2" }] });
+
+        assert_eq!(
+            serde_json::from_value::<OrderId>(response).unwrap(),
+            OrderId {
+                order_id: "1".to_owned(),
+                orders: vec![OrderId {
+                    order_id: "2".to_owned(),
+                    orders: Vec::new()
+                }]
+            }
+        );
+
+        let response = json!({ "Orders": [{ "OrderId": "2" }, { "OrderId": "3" }] });
+        assert_eq!(
+            serde_json::from_value::<OrderId>(response).unwrap().ids(),
+            vec!["2", "3"]
+        );
+    }
+}
@@ -0,0 +1,201 @@
+//! Request and response definition for
+//! <https://www.developer.saxo/openapi/referencedocs/trade/v2/orders/precheck>
+
+pub use crate::error::ErrorInfo;
+
+use serde::{Deserialize, Serialize};
+
+/// Path of the order precheck end point.
+pub const PRECHECK: &str = "trade/v2/orders/precheck";
+
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FieldGroup {
+    Costs,
+    MarginImpactBuySell,
+}
+
+/// Body of the precheck request, which is the order to check along with the field groups of
+/// the response.
+#[derive(Serialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "PascalCase")]
+pub struct Precheck<'a, T: Serialize> {
+    #[serde(flatten)]
+    pub order: &'a T,
+    pub field_groups: &'a [FieldGroup],
+}
+
+impl<'a, T: Serialize> Precheck<'a, T> {
+    /// Precheck of `order` with its estimated costs and margin impact.
+    #[must_use]
+    pub fn new(order: &'a T) -> Self {
+        Precheck {
+            order,
+            field_groups: &[FieldGroup::Costs, FieldGroup::MarginImpactBuySell],
+        }
+    }
+}
+
+#[derive(Deserialize, Debug, Default, Clone, PartialEq)]
+#[serde(rename_all = "PascalCase")]
+pub struct PrecheckResponse {
+    pub cost: Option<Cost>,
+    pub error_info: Option<ErrorInfo>,
+    pub estimated_cash_required: Option<f64>,
+    pub estimated_cash_required_currency: Option<String>,
+    pub margin_impact_buy_sell: Option<MarginImpact>,
+    pub pre_check_result: Option<PrecheckResult>,
+    /// Prechecks of the related orders
+    #[serde(default)]
+    pub orders: Vec<PrecheckResponse>,
+}
+
+impl PrecheckResponse {
+    /// The first violation of the order or of its related orders, if any. A failed precheck
+    /// without error info is still a violation.
+    #[must_use]
+    pub fn violation(&self) -> Option<ErrorInfo> {
+        self.error_info()
+            .cloned()
+            .or_else(|| self.is_error().then(ErrorInfo::unknown))
+    }
+
+    fn error_info(&self) -> Option<&ErrorInfo> {
+        self.error_info
+            .as_ref()
+            .or_else(|| self.orders.iter().find_map(PrecheckResponse::error_info))
+    }
+
+    fn is_error(&self) -> bool {
+        self.pre_check_result == Some(PrecheckResult::Error)
+            || self.orders.iter().any(PrecheckResponse::is_error)
+    }
+}
+
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PrecheckResult {
+    Ok,
+    Error,
+    /// Result not known to this crate
+    #[serde(other)]
+    Unknown,
+}
+
+/// Estimated costs of buying (long) or selling (short) the order amount.
+#[derive(Deserialize, Debug, Default, Clone, PartialEq)]
+#[serde(rename_all = "PascalCase")]
+pub struct Cost {
+    pub long: Option<CostDetails>,
+    pub short: Option<CostDetails>,
+}
+
+#[derive(Deserialize, Debug, Default, Clone, PartialEq)]
+#[serde(rename_all = "PascalCase")]
+pub struct CostDetails {
+    pub commission: Option<f64>,
+    pub exchange_fee: Option<f64>,
+    pub stamp_duty: Option<f64>,
+    pub total_cost: Option<f64>,
+    pub total_cost_pct: Option<f64>,
+}
+
+/// Margin available now, and after buying or selling the order amount.
+#[derive(Deserialize, Debug, Default, Clone, PartialEq)]
+#[serde(rename_all = "PascalCase")]
+pub struct MarginImpact {
+    pub currency: Option<String>,
+    pub initial_margin_available_buy: Option<f64>,
+    pub initial_margin_available_current: Option<f64>,
+    pub initial_margin_available_sell: Option<f64>,
+    pub margin_impact_buy: Option<f64>,
+    pub margin_impact_sell: Option<f64>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::messages::common::AssetType;
+    use crate::messages::portfolio::orders::BuySell;
+    use crate::messages::trading::orders::Order;
+    use serde_json::json;
+
+    #[test]
+    fn test_serialize_precheck() {
+        let order = Order::market("account", 21, AssetType::FxSpot, BuySell::Buy, 1000.0);
+        let value = serde_json::to_value(Precheck::new(&order)).unwrap();
+
+        assert_eq!(value["OrderType"], "Market");
+        assert_eq!(
+            value["FieldGroups"],
+            json!(["Costs", "MarginImpactBuySell"])
+        );
+    }
+
+    #[test]
+    fn test_serde_precheck_response() {
+        let response = json!({
+            "Cost": {
+                "Long": { "Commission": 3.0, "TotalCost": 3.0, "TotalCostPct": 0.03 }
+            },
+            "EstimatedCashRequired": 10_003.0,
+            "EstimatedCashRequiredCurrency": "EUR",
+            "MarginImpactBuySell": {
+                "Currency": "EUR",
+                "InitialMarginAvailableBuy": 89_997.0,
+                "InitialMarginAvailableCurrent": 100_000.0,
+                "MarginImpactBuy": 10_003.0
+            },
+            "PreCheckResult": "Ok"
+        });
+
+        let response = serde_json::from_value::<PrecheckResponse>(response).unwrap();
+
+        assert_eq!(response.pre_check_result, Some(PrecheckResult::Ok));
+        assert!(response.violation().is_none());
+        assert_eq!(response.estimated_cash_required, Some(10_003.0));
+        assert_eq!(response.cost.unwrap().long.unwrap().commission, Some(3.0));
+    }
+
+    #[test]
+    fn test_violation() {
+        let response = json!({
+            "PreCheckResult": "Error",
+            "Orders": [
+                { "PreCheckResult": "Ok" },
+                {
+                    "ErrorInfo": {
+                        "ErrorCode": "InsufficientMargin",
+                        "Message": "Order placement would exceed margin"
+                    },
+                    "PreCheckResult": "Error"
+                }
+            ]
+        });
+
+        let response = serde_json::from_value::<PrecheckResponse>(response).unwrap();
+
+        assert_eq!(
+            response.violation().unwrap().to_string(),
+            "InsufficientMargin: Order placement would exceed margin"
+        );
+    }
+
+    #[test]
+    fn test_violation_without_error_info() {
+        let response = json!({ "PreCheckResult": "Error", "Orders": [{ "PreCheckResult": "Ok" }] });
+
+        let response = serde_json::from_value::<PrecheckResponse>(response).unwrap();
+
+        assert_eq!(response.violation().unwrap().error_code, "Unknown");
+    }
+
+    #[test]
+    fn test_serde_unknown_precheck_result() {
+        let response = json!({ "PreCheckResult": "Warning" });
+
+        let response = serde_json::from_value::<PrecheckResponse>(response).unwrap();
+
+        assert_eq!(response.pre_check_result, Some(PrecheckResult::Unknown));
+        assert!(response.violation().is_none());
+    }
+}
@@ -0,0 +1,51 @@
+//! Subscription definition for
+//! <https://www.developer.saxo/openapi/referencedocs/trade/v1/prices>
+//!
+//! Snapshots and deltas have the same fields as the info prices, see
+//! [`super::infoprices::Response`].
+
+use super::infoprices::FieldGroup;
+use crate::messages::common::{AssetType, FieldGroups};
+use crate::streaming::subscription::Subscription;
+
+use serde_json::json;
+
+/// Path of the price subscriptions end point.
+pub const SUBSCRIPTIONS: &str = "trade/v1/prices/subscriptions";
+
+/// Subscription to the tradable price of a single instrument.
+#[must_use]
+pub fn subscription(
+    reference_id: &str,
+    uic: i32,
+    asset_type: &AssetType,
+    field_groups: &FieldGroups<FieldGroup>,
+) -> Subscription {
+    let arguments = json!({
+        "AssetType": asset_type,
+        "FieldGroups": field_groups,
+        "Uic": uic
+    });
+
+    Subscription::new(SUBSCRIPTIONS, reference_id, arguments)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_subscription() {
+        let subscription = subscription("price", 21, &AssetType::FxSpot, &FieldGroup::defaults());
+
+        assert_eq!(subscription.endpoint, SUBSCRIPTIONS);
+        assert_eq!(
+            subscription.arguments,
+            json!({
+                "AssetType": "FxSpot",
+                "FieldGroups": ["PriceInfo", "PriceInfoDetails", "Quote"],
+                "Uic": 21
+            })
+        );
+    }
+}
@@ -0,0 +1,307 @@
+//! Related orders placed in a single request to `trade/v2/orders`, see
+//! <https://www.developer.saxo/openapi/learn/order-placement>
+
+use super::orders::Order;
+use super::OrderError;
+use crate::messages::portfolio::orders::{BuySell, OrderDuration, OrderType};
+
+use serde::Serialize;
+
+/// Body of the request placing an entry order along with its take profit and stop loss, or a
+/// pair of one-cancels-other orders.
+#[derive(Serialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "PascalCase")]
+pub struct RelatedOrders {
+    #[serde(flatten, skip_serializing_if = "Option::is_none")]
+    pub entry: Option<Order>,
+    pub orders: Vec<Order>,
+}
+
+impl RelatedOrders {
+    /// Starts building related orders attached to `entry`, placed once it is filled.
+    #[must_use]
+    pub fn entry(entry: Order) -> RelatedOrdersBuilder {
+        RelatedOrdersBuilder {
+            entry,
+            take_profit: None,
+            stop_loss: None,
+            trailing_stop_loss: None,
+        }
+    }
+
+    /// Two orders for the same instrument, where filling one cancels the other.
+    pub fn one_cancels_other(first: Order, second: Order) -> Result<Self, OrderError> {
+        if first.account_key != second.account_key
+            || first.uic != second.uic
+            || first.asset_type != second.asset_type
+        {
+            return Err(OrderError::MismatchedInstrument);
+        }
+        if first.order_type == OrderType::Market || second.order_type == OrderType::Market {
+            return Err(OrderError::MarketOrderInOco);
+        }
+
+        Ok(RelatedOrders {
+            entry: None,
+            orders: vec![first, second],
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct TrailingStop {
+    price: f64,
+    distance: f64,
+    step: f64,
+}
+
+/// Attaches take profit and stop loss orders to an entry order, validating them on
+/// [`RelatedOrdersBuilder::build`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct RelatedOrdersBuilder {
+    entry: Order,
+    take_profit: Option<f64>,
+    stop_loss: Option<f64>,
+    trailing_stop_loss: Option<TrailingStop>,
+}
+
+impl RelatedOrdersBuilder {
+    /// Limit order closing the position at `price`.
+    #[must_use]
+    pub fn take_profit(mut self, price: f64) -> Self {
+        self.take_profit = Some(price);
+        self
+    }
+
+    /// Stop order closing the position at `price`.
+    #[must_use]
+    pub fn stop_loss(mut self, price: f64) -> Self {
+        self.stop_loss = Some(price);
+        self
+    }
+
+    /// Stop order starting at `price`, which follows the market at `distance` in increments
+    /// of `step`.
+    #[must_use]
+    pub fn trailing_stop_loss(mut self, price: f64, distance: f64, step: f64) -> Self {
+        self.trailing_stop_loss = Some(TrailingStop {
+            price,
+            distance,
+            step,
+        });
+        self
+    }
+
+    pub fn build(self) -> Result<RelatedOrders, OrderError> {
+        let entry = &self.entry;
+        if !matches!(
+            entry.order_type,
+            OrderType::Market | OrderType::Limit | OrderType::Stop | OrderType::StopLimit
+        ) {
+            return Err(OrderError::InvalidEntryType(entry.order_type));
+        }
+
+        let stop_loss = match (self.stop_loss, self.trailing_stop_loss) {
+            (Some(_), Some(_)) => return Err(OrderError::ConflictingStopLoss),
+            (Some(price), None) => Some(price),
+            (None, Some(trailing_stop)) => {
+                if trailing_stop.distance <= 0.0 || trailing_stop.step <= 0.0 {
+                    return Err(OrderError::InvalidTrailingStop);
+                }
+                Some(trailing_stop.price)
+            }
+            (None, None) => None,
+        };
+        if self.take_profit.is_none() && stop_loss.is_none() {
+            return Err(OrderError::NoRelatedOrders);
+        }
+
+        // Prices above the reference are profitable for a buy entry, and losing for a sell entry
+        let profitable = |price: f64, reference: f64| match entry.buy_sell {
+            BuySell::Buy => price > reference,
+            BuySell::Sell => price < reference,
+        };
+        if let (Some(take_profit), Some(entry_price)) = (self.take_profit, entry.order_price) {
+            if !profitable(take_profit, entry_price) {
+                return Err(OrderError::TakeProfitNotProfitable);
+            }
+        }
+        if let Some(stop_loss) = stop_loss {
+            let references = [entry.order_price, self.take_profit];
+            if references
+                .into_iter()
+                .flatten()
+                .any(|reference| !profitable(reference, stop_loss))
+            {
+                return Err(OrderError::StopLossNotProtective);
+            }
+        }
+
+        let mut orders = Vec::new();
+        if let Some(price) = self.take_profit {
+            orders.push(self.leg(OrderType::Limit).price(price));
+        }
+        if let Some(price) = self.stop_loss {
+            orders.push(self.leg(OrderType::StopIfTraded).price(price));
+        }
+        if let Some(trailing_stop) = self.trailing_stop_loss {
+            let mut order = self
+                .leg(OrderType::TrailingStopIfTraded)
+                .price(trailing_stop.price);
+            order.trailing_stop_distance_to_market = Some(trailing_stop.distance);
+            order.trailing_stop_step = Some(trailing_stop.step);
+            orders.push(order);
+        }
+
+        Ok(RelatedOrders {
+            entry: Some(self.entry),
+            orders,
+        })
+    }
+
+    /// Order closing the position opened by the entry order.
+    fn leg(&self, order_type: OrderType) -> Order {
+        let entry = &self.entry;
+        let buy_sell = match entry.buy_sell {
+            BuySell::Buy => BuySell::Sell,
+            BuySell::Sell => BuySell::Buy,
+        };
+
+        let mut order = Order::new(
+            &entry.account_key,
+            entry.uic,
+            entry.asset_type.clone(),
+            buy_sell,
+            entry.amount,
+            order_type,
+        )
+        .duration(OrderDuration::GoodTillCancel);
+        order.manual_order = entry.manual_order;
+        order
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::messages::common::AssetType;
+    use serde_json::json;
+
+    fn entry() -> Order {
+        Order::limit("account", 21, AssetType::FxSpot, BuySell::Buy, 1000.0, 1.05)
+    }
+
+    #[test]
+    fn test_serialize_related_orders() {
+        let orders = RelatedOrders::entry(entry())
+            .take_profit(1.1)
+            .trailing_stop_loss(1.0, 0.005, 0.001)
+            .build()
+            .unwrap();
+
+        let value = serde_json::to_value(&orders).unwrap();
+        assert_eq!(value["OrderType"], "Limit");
+        assert_eq!(value["OrderPrice"], 1.05);
+        assert_eq!(
+            value["Orders"],
+            json!([
+                {
+                    "AccountKey": "account",
+                    "Amount": 1000.0,
+                    "AssetType": "FxSpot",
+                    "BuySell": "Sell",
+                    "ManualOrder": false,
+                    "OrderDuration": { "DurationType": "GoodTillCancel" },
+                    "OrderPrice": 1.1,
+                    "OrderType": "Limit",
+                    "Uic": 21
+                },
+                {
+                    "AccountKey": "account",
+                    "Amount": 1000.0,
+                    "AssetType": "FxSpot",
+                    "BuySell": "Sell",
+                    "ManualOrder": false,
+                    "OrderDuration": { "DurationType": "GoodTillCancel" },
+                    "OrderPrice": 1.0,
+                    "OrderType": "TrailingStopIfTraded",
+                    "TrailingStopDistanceToMarket": 0.005,
+                    "TrailingStopStep": 0.001,
+                    "Uic": 21
+                }
+            ])
+        );
+    }
+
+    #[test]
+    fn test_invalid_related_orders() {
+        assert_eq!(
+            RelatedOrders::entry(entry()).build(),
+            Err(OrderError::NoRelatedOrders)
+        );
+        assert_eq!(
+            RelatedOrders::entry(entry())
+                .stop_loss(1.0)
+                .trailing_stop_loss(1.0, 0.005, 0.001)
+                .build(),
+            Err(OrderError::ConflictingStopLoss)
+        );
+        assert_eq!(
+            RelatedOrders::entry(entry()).take_profit(1.0).build(),
+            Err(OrderError::TakeProfitNotProfitable)
+        );
+        assert_eq!(
+            RelatedOrders::entry(entry()).stop_loss(1.06).build(),
+            Err(OrderError::StopLossNotProtective)
+        );
+        assert_eq!(
+            RelatedOrders::entry(entry())
+                .trailing_stop_loss(1.0, 0.0, 0.001)
+                .build(),
+            Err(OrderError::InvalidTrailingStop)
+        );
+
+        // Without an entry price, the stop loss is checked against the take profit
+        let entry = Order::market("account", 21, AssetType::FxSpot, BuySell::Sell, 1000.0);
+        assert_eq!(
+            RelatedOrders::entry(entry.clone())
+                .take_profit(1.0)
+                .stop_loss(0.9)
+                .build(),
+            Err(OrderError::StopLossNotProtective)
+        );
+        assert!(RelatedOrders::entry(entry)
+            .take_profit(0.9)
+            .stop_loss(1.0)
+            .build()
+            .is_ok());
+    }
+
+    #[test]
+    fn test_one_cancels_other() {
+        let take_profit =
+            Order::limit("account", 21, AssetType::FxSpot, BuySell::Sell, 1000.0, 1.1);
+        let stop_loss = Order::stop("account", 21, AssetType::FxSpot, BuySell::Sell, 1000.0, 1.0);
+
+        let orders = RelatedOrders::one_cancels_other(take_profit.clone(), stop_loss).unwrap();
+        let value = serde_json::to_value(&orders).unwrap();
+        assert_eq!(value.as_object().unwrap().len(), 1);
+        assert_eq!(value["Orders"].as_array().unwrap().len(), 2);
+
+        assert_eq!(
+            RelatedOrders::one_cancels_other(
+                take_profit.clone(),
+                Order::stop("account", 22, AssetType::FxSpot, BuySell::Sell, 1000.0, 1.0)
+            ),
+            Err(OrderError::MismatchedInstrument)
+        );
+        assert_eq!(
+            RelatedOrders::one_cancels_other(
+                take_profit,
+                Order::market("account", 21, AssetType::FxSpot, BuySell::Sell, 1000.0)
+            ),
+            Err(OrderError::MarketOrderInOco)
+        );
+    }
+}
@@ -0,0 +1,161 @@
+//! Control messages sent by the server, see
+//! <https://www.developer.saxo/openapi/learn/plain-websocket-streaming#PlainWebSocketStreaming-Controlmessages>
+
+use serde::Deserialize;
+use strum::EnumString;
+
+#[derive(Deserialize, Debug, PartialEq)]
+#[serde(tag = "ReferenceId")]
+pub enum ControlMessage {
+    /// Sent for subscriptions that haven't received any data for a while
+    #[serde(rename = "_heartbeat")]
+    Heartbeat {
+        #[serde(rename = "Heartbeats")]
+        heartbeats: Vec<Heartbeat>,
+    },
+    /// The server lost data for the given subscriptions, or for all of them if none are given,
+    /// so they must be recreated
+    #[serde(rename = "_resetsubscriptions")]
+    ResetSubscriptions {
+        #[serde(rename = "TargetReferenceIds", default)]
+        target_reference_ids: Vec<String>,
+    },
+    /// The server is closing the connection, e.g. because the token expired
+    #[serde(rename = "_disconnect")]
+    Disconnect {},
+}
+
+impl ControlMessage {
+    /// Control messages use reference ids starting with an underscore.
+    #[must_use]
+    pub fn is_control(reference_id: &str) -> bool {
+        reference_id.starts_with('_')
+    }
+
+    /// Parses the payload of a control message, which is either a single message or an array.
+    pub fn parse(payload: &[u8]) -> serde_json::Result<Vec<ControlMessage>> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum OneOrMany {
+            One(ControlMessage),
+            Many(Vec<ControlMessage>),
+        }
+
+        Ok(match serde_json::from_slice(payload)? {
+            OneOrMany::One(message) => vec![message],
+            OneOrMany::Many(messages) => messages,
+        })
+    }
+}
+
+#[derive(Deserialize, Debug, PartialEq)]
+#[serde(rename_all = "PascalCase")]
+pub struct Heartbeat {
+    pub originating_reference_id: String,
+    pub reason: HeartbeatReason,
+}
+
+#[derive(EnumString, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HeartbeatReason {
+    /// No new data since the last message
+    NoNewData,
+    /// No data will be sent for a while, e.g. outside trading hours
+    SubscriptionTemporarilyDisabled,
+    /// No data will be sent again, e.g. because of missing permissions
+    SubscriptionPermanentlyDisabled,
+    #[serde(other)]
+    Unknown,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use serde_json::json;
+
+    #[test]
+    fn test_parse_heartbeat() {
+        let payload = json!([{
+            "ReferenceId": "_heartbeat",
+            "Heartbeats": [
+                { "OriginatingReferenceId": "foo", "Reason": "NoNewData" },
+                { "OriginatingReferenceId": "bar", "Reason": "SubscriptionPermanentlyDisabled" }
+            ]
+        }]);
+
+        assert_eq!(
+            ControlMessage::parse(payload.to_string().as_bytes()).unwrap(),
+            vec![ControlMessage::Heartbeat {
+                heartbeats: vec![
+                    Heartbeat {
+                        originating_reference_id: "foo".to_owned(),
+                        reason: HeartbeatReason::NoNewData,
+                    },
+                    Heartbeat {
+                        originating_reference_id: "bar".to_owned(),
+                        reason: HeartbeatReason::SubscriptionPermanentlyDisabled,
+                    },
+                ],
+            }]
+        );
+    }
+
+    #[test]
+    fn test_parse_unknown_heartbeat_reason() {
+        let payload = json!({
+            "ReferenceId": "_heartbeat",
+            "Heartbeats": [{ "OriginatingReferenceId": "foo", "Reason": "SomethingNew" }]
+        });
+
+        assert_eq!(
+            ControlMessage::parse(payload.to_string().as_bytes()).unwrap(),
+            vec![ControlMessage::Heartbeat {
+                heartbeats: vec![Heartbeat {
+                    originating_reference_id: "foo".to_owned(),
+                    reason: HeartbeatReason::Unknown,
+                }],
+            }]
+        );
+    }
+
+    #[test]
+    fn test_parse_reset_subscriptions() {
+        let payload = json!({
+            "ReferenceId": "_resetsubscriptions",
+            "Timestamp": "2023-05-21T00:00:00Z",
+            "TargetReferenceIds": ["foo"]
+        });
+
+        assert_eq!(
+            ControlMessage::parse(payload.to_string().as_bytes()).unwrap(),
+            vec![ControlMessage::ResetSubscriptions {
+                target_reference_ids: vec!["foo".to_owned()],
+            }]
+        );
+
+        let payload = json!({ "ReferenceId": "_resetsubscriptions" });
+        assert_eq!(
+            ControlMessage::parse(payload.to_string().as_bytes()).unwrap(),
+            vec![ControlMessage::ResetSubscriptions {
+                target_reference_ids: Vec::new(),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_parse_disconnect() {
+        let payload =
+            json!([{ "ReferenceId": "_disconnect", "Timestamp": "2023-05-21T00:00:00Z" }]);
+
+        assert_eq!(
+            ControlMessage::parse(payload.to_string().as_bytes()).unwrap(),
+            vec![ControlMessage::Disconnect {}]
+        );
+    }
+
+    #[test]
+    fn test_is_control() {
+        assert!(ControlMessage::is_control("_heartbeat"));
+        assert!(!ControlMessage::is_control("foo"));
+    }
+}
@@ -0,0 +1,275 @@
+//! Decoder for the binary frames sent on the streaming WebSocket. Every frame holds one or more
+//! messages laid out as follows, with all integers little endian:
+//!
+//! | Bytes | Field               |
+//! |-------|---------------------|
+//! | 8     | Message id          |
+//! | 2     | Reserved            |
+//! | 1     | Reference id size   |
+//! | n     | Reference id, ASCII |
+//! | 1     | Payload format      |
+//! | 4     | Payload size        |
+//! | n     | Payload             |
+
+use crate::error::FrameError;
+
+const MESSAGE_ID_SIZE: usize = 8;
+const RESERVED_SIZE: usize = 2;
+const PAYLOAD_SIZE_SIZE: usize = 4;
+
+/// Payload of a streaming message, borrowed from the frame it was decoded from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Payload<'a> {
+    Json(&'a [u8]),
+    Protobuf(&'a [u8]),
+}
+
+impl<'a> Payload<'a> {
+    #[must_use]
+    pub fn bytes(&self) -> &'a [u8] {
+        match self {
+            Payload::Json(bytes) | Payload::Protobuf(bytes) => bytes,
+        }
+    }
+
+    #[must_use]
+    pub fn format(&self) -> PayloadFormat {
+        match self {
+            Payload::Json(_) => PayloadFormat::Json,
+            Payload::Protobuf(_) => PayloadFormat::Protobuf,
+        }
+    }
+}
+
+/// Payload format byte of a streaming message.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PayloadFormat {
+    Json = 0,
+    Protobuf = 1,
+}
+
+impl TryFrom<u8> for PayloadFormat {
+    type Error = FrameError;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(PayloadFormat::Json),
+            1 => Ok(PayloadFormat::Protobuf),
+            _ => Err(FrameError::UnknownPayloadFormat(value)),
+        }
+    }
+}
+
+/// A single decoded message, borrowing the reference id and payload from its frame.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StreamMessage<'a> {
+    pub message_id: u64,
+    pub reference_id: &'a str,
+    pub payload: Payload<'a>,
+}
+
+/// Iterator over the messages of a frame, see [`decode`].
+///
+/// Decoding stops after the first error, as the position of the following message is unknown.
+#[derive(Debug, Clone)]
+pub struct Frame<'a> {
+    data: &'a [u8],
+    offset: usize,
+}
+
+/// Decodes the messages packed in a binary WebSocket frame.
+#[must_use]
+pub fn decode(data: &[u8]) -> Frame<'_> {
+    Frame { data, offset: 0 }
+}
+
+impl<'a> Frame<'a> {
+    fn take(&mut self, len: usize) -> Option<&'a [u8]> {
+        let bytes = self.data.get(self.offset..)?.get(..len)?;
+        self.offset += len;
+        Some(bytes)
+    }
+
+    fn decode_message(&mut self) -> Result<StreamMessage<'a>, FrameError> {
+        let start = self.offset;
+        let truncated = || FrameError::Truncated(start);
+
+        let message_id = self
+            .take(MESSAGE_ID_SIZE)
+            .and_then(|bytes| bytes.try_into().ok())
+            .map(u64::from_le_bytes)
+            .ok_or_else(truncated)?;
+        self.take(RESERVED_SIZE).ok_or_else(truncated)?;
+
+        let reference_id_size = self.take(1).ok_or_else(truncated)?[0];
+        let reference_id = self
+            .take(usize::from(reference_id_size))
+            .ok_or_else(truncated)?;
+        let reference_id =
+            std::str::from_utf8(reference_id).map_err(|_| FrameError::InvalidReferenceId)?;
+
+        let format = PayloadFormat::try_from(self.take(1).ok_or_else(truncated)?[0])?;
+        let payload_size = self
+            .take(PAYLOAD_SIZE_SIZE)
+            .and_then(|bytes| bytes.try_into().ok())
+            .map(u32::from_le_bytes)
+            .ok_or_else(truncated)?;
+        let payload = usize::try_from(payload_size)
+            .ok()
+            .and_then(|size| self.take(size))
+            .ok_or_else(truncated)?;
+
+        Ok(StreamMessage {
+            message_id,
+            reference_id,
+            payload: match format {
+                PayloadFormat::Json => Payload::Json(payload),
+                PayloadFormat::Protobuf => Payload::Protobuf(payload),
+            },
+        })
+    }
+}
+
+impl<'a> Iterator for Frame<'a> {
+    type Item = Result<StreamMessage<'a>, FrameError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.offset >= self.data.len() {
+            return None;
+        }
+
+        let message = self.decode_message();
+        if message.is_err() {
+            // Skip the rest of the frame
+            self.offset = self.data.len();
+        }
+
+        Some(message)
+    }
+}
+
+impl std::iter::FusedIterator for Frame<'_> {}
+
+/// Encodes a single message, the inverse of [`decode`].
+#[cfg(test)]
+pub(crate) fn encode(message_id: u64, reference_id: &str, payload: Payload) -> Vec<u8> {
+    let mut data = message_id.to_le_bytes().to_vec();
+    data.extend_from_slice(&[0; RESERVED_SIZE]);
+    data.push(u8::try_from(reference_id.len()).unwrap());
+    data.extend_from_slice(reference_id.as_bytes());
+    data.push(payload.format() as u8);
+    data.extend_from_slice(&u32::try_from(payload.bytes().len()).unwrap().to_le_bytes());
+    data.extend_from_slice(payload.bytes());
+    data
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use proptest::prelude::*;
+
+    #[test]
+    fn test_decode() {
+        let mut data = encode(1, "foo", Payload::Json(b"{\"Foo\":1}"));
+        data.extend(encode(2, "bar", Payload::Protobuf(&[1, 2, 3])));
+
+        let messages = decode(&data).collect::<Result<Vec<_>, _>>().unwrap();
+        assert_eq!(
+            messages,
+            vec![
+                StreamMessage {
+                    message_id: 1,
+                    reference_id: "foo",
+                    payload: Payload::Json(b"{\"Foo\":1}"),
+                },
+                StreamMessage {
+                    message_id: 2,
+                    reference_id: "bar",
+                    payload: Payload::Protobuf(&[1, 2, 3]),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_decode_empty() {
+        assert_eq!(decode(&[]).count(), 0);
+    }
+
+    #[test]
+    fn test_decode_unknown_payload_format() {
+        let mut data = encode(1, "foo", Payload::Json(b"{}"));
+        data[14] = 7;
+
+        let mut frame = decode(&data);
+        assert_eq!(frame.next(), Some(Err(FrameError::UnknownPayloadFormat(7))));
+        assert_eq!(frame.next(), None);
+    }
+
+    #[test]
+    fn test_decode_invalid_reference_id() {
+        let mut data = encode(1, "foo", Payload::Json(b"{}"));
+        data[11] = 0xff;
+
+        assert_eq!(
+            decode(&data).next(),
+            Some(Err(FrameError::InvalidReferenceId))
+        );
+    }
+
+    fn arb_message() -> impl Strategy<Value = (u64, String, bool, Vec<u8>)> {
+        (
+            any::<u64>(),
+            "[A-Za-z0-9_]{0,50}",
+            any::<bool>(),
+            proptest::collection::vec(any::<u8>(), 0..256),
+        )
+    }
+
+    fn payload(protobuf: bool, bytes: &[u8]) -> Payload<'_> {
+        if protobuf {
+            Payload::Protobuf(bytes)
+        } else {
+            Payload::Json(bytes)
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn prop_decode_arbitrary_bytes_never_panics(data in proptest::collection::vec(any::<u8>(), 0..512)) {
+            for message in decode(&data).flatten() {
+                prop_assert!(message.payload.bytes().len() <= data.len());
+            }
+        }
+
+        #[test]
+        fn prop_decode_round_trip(messages in proptest::collection::vec(arb_message(), 0..8)) {
+            let data = messages
+                .iter()
+                .flat_map(|(id, reference_id, protobuf, bytes)| {
+                    encode(*id, reference_id, payload(*protobuf, bytes))
+                })
+                .collect::<Vec<_>>();
+
+            let decoded = decode(&data).collect::<Result<Vec<_>, _>>().unwrap();
+            prop_assert_eq!(decoded.len(), messages.len());
+            for (decoded, (id, reference_id, protobuf, bytes)) in decoded.iter().zip(&messages) {
+                prop_assert_eq!(decoded.message_id, *id);
+                prop_assert_eq!(decoded.reference_id, reference_id.as_str());
+                prop_assert_eq!(decoded.payload, payload(*protobuf, bytes));
+            }
+        }
+
+        #[test]
+        fn prop_decode_truncated(message in arb_message(), cut in any::<prop::sample::Index>()) {
+            let (id, reference_id, protobuf, bytes) = message;
+            let data = encode(id, &reference_id, payload(protobuf, &bytes));
+            let truncated = &data[..cut.index(data.len())];
+
+            let decoded = decode(truncated).collect::<Vec<_>>();
+            prop_assert!(truncated.is_empty() || decoded.len() == 1);
+            prop_assert!(decoded.iter().all(|message| matches!(message, Err(FrameError::Truncated(0)))));
+        }
+    }
+}
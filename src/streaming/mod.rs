@@ -0,0 +1,1442 @@
+//! WebSocket streaming connection, see
+//! <https://www.developer.saxo/openapi/learn/plain-websocket-streaming>
+
+pub mod control;
+pub mod frame;
+pub mod quotes;
+pub mod state;
+pub mod subscription;
+
+use crate::auth::provider::TokenProvider;
+use crate::error::SaxoError;
+use control::{ControlMessage, HeartbeatReason};
+use frame::{PayloadFormat, StreamMessage};
+use subscription::{
+    Subscriber, Subscription, SubscriptionResponse, SubscriptionStatus, SubscriptionUpdate,
+};
+
+use futures_util::{SinkExt, StreamExt};
+use serde::de::DeserializeOwned;
+use tokio::net::TcpStream;
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+use tokio_tungstenite::tungstenite::client::IntoClientRequest;
+use tokio_tungstenite::tungstenite::error::{Error, ProtocolError};
+use tokio_tungstenite::tungstenite::http::HeaderValue;
+use tokio_tungstenite::tungstenite::Message as WsMessage;
+use tokio_tungstenite::{MaybeTlsStream, WebSocketStream};
+
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError, Weak};
+use std::time::Duration;
+
+/// Interval at which a ping is sent to keep the connection from idling out, and at which the
+/// token is checked for a refresh.
+const KEEP_ALIVE_INTERVAL: Duration = Duration::from_secs(30);
+
+type Socket = WebSocketStream<MaybeTlsStream<TcpStream>>;
+
+/// A single message received on the streaming connection.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Message {
+    pub message_id: u64,
+    pub reference_id: String,
+    pub format: PayloadFormat,
+    pub payload: Vec<u8>,
+}
+
+impl Message {
+    /// Deserializes the JSON payload of the message.
+    pub fn json<T: DeserializeOwned>(&self) -> serde_json::Result<T> {
+        match self.format {
+            PayloadFormat::Json => serde_json::from_slice(&self.payload),
+            PayloadFormat::Protobuf => Err(serde::de::Error::custom("Payload is not JSON")),
+        }
+    }
+}
+
+impl From<StreamMessage<'_>> for Message {
+    fn from(message: StreamMessage<'_>) -> Self {
+        Message {
+            message_id: message.message_id,
+            reference_id: message.reference_id.to_owned(),
+            format: message.payload.format(),
+            payload: message.payload.bytes().to_vec(),
+        }
+    }
+}
+
+/// Events observed on the streaming connection, besides the data of the subscriptions.
+#[derive(Debug)]
+pub enum StreamingEvent {
+    /// A heartbeat was received for a subscription without new data
+    Heartbeat {
+        reference_id: String,
+        reason: HeartbeatReason,
+    },
+    /// The subscription was recreated on request of the server, and a new snapshot has been
+    /// sent on its channel. A reset subscription is recreated under its reference id followed by
+    /// a `-<n>` suffix, which is the reference id of its following messages, so that those of the
+    /// previous subscription are told apart and dropped.
+    SubscriptionReset { reference_id: String },
+    /// The subscription could not be recreated on request of the server
+    SubscriptionResetFailed {
+        reference_id: String,
+        error: SaxoError,
+    },
+    /// A control message could not be decoded and was ignored. If it was a reset, the affected
+    /// subscriptions are not recreated.
+    InvalidControlMessage(SaxoError),
+    /// The server asked the client to disconnect
+    Disconnected,
+    /// The connection dropped, or a frame could not be decoded, and is being reestablished.
+    /// After an ill-formed frame the stream is resumed from the last decoded message, so the
+    /// messages of the rest of the frame are sent again.
+    ConnectionLost(SaxoError),
+    /// The connection has been reestablished. If the stream could be resumed no messages were
+    /// lost, otherwise the subscriptions have been recreated under a new context id and new
+    /// snapshots are sent on their channels.
+    Reconnected { context_id: String, resumed: bool },
+    /// The context could not be authorized with a refreshed token, so the server will drop the
+    /// connection once the previous token expires
+    AuthorizationFailed(SaxoError),
+}
+
+/// Backoff between attempts to reestablish a dropped connection.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReconnectPolicy {
+    pub initial_delay: Duration,
+    pub max_delay: Duration,
+    /// Gives up after this many failed attempts, or never if `None`
+    pub max_attempts: Option<u32>,
+}
+
+impl Default for ReconnectPolicy {
+    fn default() -> Self {
+        ReconnectPolicy {
+            initial_delay: Duration::from_millis(500),
+            max_delay: Duration::from_secs(30),
+            max_attempts: None,
+        }
+    }
+}
+
+struct Entry {
+    /// Reference id the channel was registered with
+    reference_id: String,
+    /// Number of times the subscription has been reset
+    resets: u32,
+    sender: mpsc::UnboundedSender<SubscriptionUpdate>,
+    subscription: Option<Subscription>,
+    status: SubscriptionStatus,
+    /// Deltas held back while the snapshot of the subscription is awaited
+    pending: Option<Vec<Message>>,
+}
+
+/// Entries by the reference id of their subscription on the server.
+type Entries = HashMap<String, Entry>;
+
+/// Subscription to create again for a registered channel.
+struct Recreation {
+    reference_id: String,
+    /// Subscription to delete first, when the subscription is reset
+    previous: Option<Subscription>,
+    subscription: Subscription,
+}
+
+struct Session {
+    context_id: String,
+    /// Token the context is authorized with
+    token: String,
+    last_message_id: Option<u64>,
+    reconnects: u32,
+}
+
+/// State shared between the connection and its tasks.
+struct Shared {
+    url: String,
+    token: Arc<dyn TokenProvider>,
+    base_context_id: String,
+    policy: ReconnectPolicy,
+    subscriber: Arc<dyn Subscriber>,
+    session: Mutex<Session>,
+    entries: Mutex<Entries>,
+    tasks: Mutex<Vec<JoinHandle<()>>>,
+}
+
+impl Shared {
+    fn lock(&self) -> MutexGuard<'_, Entries> {
+        self.entries.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Removes the entry registered with `reference_id`, whatever its reference id on the server.
+    fn remove(&self, reference_id: &str) -> Option<Entry> {
+        let mut entries = self.lock();
+        let key = entries
+            .iter()
+            .find(|(_, entry)| entry.reference_id == reference_id)
+            .map(|(key, _)| key.clone())?;
+        entries.remove(&key)
+    }
+
+    fn session(&self) -> MutexGuard<'_, Session> {
+        self.session.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn context_id(&self) -> String {
+        self.session().context_id.clone()
+    }
+
+    /// Runs a task in the background until it completes or the connection is dropped.
+    fn spawn(&self, task: impl Future<Output = ()> + Send + 'static) {
+        let mut tasks = self.tasks.lock().unwrap_or_else(PoisonError::into_inner);
+        tasks.retain(|task| !task.is_finished());
+        tasks.push(tokio::spawn(task));
+    }
+
+    /// Url resuming the stream, if the connect url follows the Saxo naming.
+    fn reconnect_url(&self) -> Option<String> {
+        self.url
+            .strip_suffix("/connect")
+            .map(|base| format!("{base}/reconnect"))
+    }
+}
+
+/// An open streaming connection for a single context id.
+///
+/// Incoming messages are dispatched to the channel registered for their reference id, and
+/// control messages are handled by the connection itself. If the connection drops, it is
+/// reestablished as told by its [`ReconnectPolicy`]. The connection is closed when dropped.
+pub struct StreamingConnection {
+    shared: Arc<Shared>,
+    events: mpsc::UnboundedReceiver<StreamingEvent>,
+    task: JoinHandle<Result<(), SaxoError>>,
+}
+
+impl StreamingConnection {
+    /// Opens the streaming WebSocket at `url` for `context_id`, reconnecting with the default
+    /// policy. Subscriptions are created and recreated through `subscriber`.
+    pub async fn connect(
+        url: &str,
+        token: Arc<dyn TokenProvider>,
+        context_id: &str,
+        subscriber: Arc<dyn Subscriber>,
+    ) -> Result<Self, SaxoError> {
+        Self::connect_with_policy(
+            url,
+            token,
+            context_id,
+            subscriber,
+            ReconnectPolicy::default(),
+        )
+        .await
+    }
+
+    pub async fn connect_with_policy(
+        url: &str,
+        token: Arc<dyn TokenProvider>,
+        context_id: &str,
+        subscriber: Arc<dyn Subscriber>,
+        policy: ReconnectPolicy,
+    ) -> Result<Self, SaxoError> {
+        let access_token = token.access_token().await?;
+        let socket = Self::open(url, &access_token, context_id, None).await?;
+
+        let shared = Arc::new(Shared {
+            url: url.to_owned(),
+            token,
+            base_context_id: context_id.to_owned(),
+            policy,
+            subscriber,
+            session: Mutex::new(Session {
+                context_id: context_id.to_owned(),
+                token: access_token,
+                last_message_id: None,
+                reconnects: 0,
+            }),
+            entries: Mutex::default(),
+            tasks: Mutex::default(),
+        });
+        let (events_sender, events) = mpsc::unbounded_channel();
+        let task = tokio::spawn(Self::run(socket, shared.clone(), events_sender));
+
+        Ok(StreamingConnection {
+            shared,
+            events,
+            task,
+        })
+    }
+
+    /// The current context id, which changes if the connection had to be reestablished without
+    /// resuming the stream.
+    #[must_use]
+    pub fn context_id(&self) -> String {
+        self.shared.context_id()
+    }
+
+    /// Registers a channel receiving every message with the given reference id.
+    /// Subscribing twice to the same reference id replaces the previous channel.
+    ///
+    /// Such channels are not recreated along with the subscriptions of the connection, see
+    /// [`StreamingConnection::create_subscription`].
+    #[must_use]
+    pub fn subscribe(&self, reference_id: &str) -> mpsc::UnboundedReceiver<SubscriptionUpdate> {
+        self.register(reference_id, None)
+    }
+
+    pub fn unsubscribe(&self, reference_id: &str) {
+        self.shared.remove(reference_id);
+    }
+
+    /// Creates the subscription on the server and registers a channel for its updates.
+    /// The initial snapshot is part of the returned response.
+    pub async fn create_subscription(
+        &self,
+        subscription: Subscription,
+    ) -> Result<
+        (
+            SubscriptionResponse,
+            mpsc::UnboundedReceiver<SubscriptionUpdate>,
+        ),
+        SaxoError,
+    > {
+        let receiver = self.register(&subscription.reference_id, Some(subscription.clone()));
+
+        match Self::subscribe_entry(&self.shared, &self.context_id(), &subscription, false).await {
+            Ok(response) => Ok((response, receiver)),
+            Err(err) => {
+                self.unsubscribe(&subscription.reference_id);
+                Err(err)
+            }
+        }
+    }
+
+    /// Deletes a subscription created with [`StreamingConnection::create_subscription`].
+    pub async fn delete_subscription(&self, reference_id: &str) -> Result<(), SaxoError> {
+        let entry = self.shared.remove(reference_id);
+
+        match entry.and_then(|entry| entry.subscription) {
+            Some(subscription) => {
+                self.shared
+                    .subscriber
+                    .unsubscribe(&self.context_id(), &subscription)
+                    .await
+            }
+            None => Ok(()),
+        }
+    }
+
+    /// Guard deleting the subscription with the given reference id when dropped, as long as
+    /// the connection is still open.
+    #[must_use]
+    pub fn guard(&self, reference_id: &str) -> SubscriptionGuard {
+        SubscriptionGuard {
+            shared: Arc::downgrade(&self.shared),
+            reference_id: reference_id.to_owned(),
+        }
+    }
+
+    /// Status of the subscription with the given reference id, if it is registered.
+    #[must_use]
+    pub fn status(&self, reference_id: &str) -> Option<SubscriptionStatus> {
+        self.shared
+            .lock()
+            .values()
+            .find(|entry| entry.reference_id == reference_id)
+            .map(|entry| entry.status)
+    }
+
+    /// Waits for the next event. Returns `None` once the connection is closed.
+    pub async fn next_event(&mut self) -> Option<StreamingEvent> {
+        self.events.recv().await
+    }
+
+    /// Waits until the connection is closed, returning the error that ended it, if any.
+    pub async fn closed(&mut self) -> Result<(), SaxoError> {
+        match (&mut self.task).await {
+            Ok(result) => result,
+            Err(err) => Err(SaxoError::HTTPError(Box::new(err))),
+        }
+    }
+
+    fn register(
+        &self,
+        reference_id: &str,
+        subscription: Option<Subscription>,
+    ) -> mpsc::UnboundedReceiver<SubscriptionUpdate> {
+        let (sender, receiver) = mpsc::unbounded_channel();
+        self.shared.remove(reference_id);
+        self.shared.lock().insert(
+            reference_id.to_owned(),
+            Entry {
+                reference_id: reference_id.to_owned(),
+                resets: 0,
+                sender,
+                subscription,
+                status: SubscriptionStatus::default(),
+                pending: None,
+            },
+        );
+        receiver
+    }
+
+    async fn open(
+        url: &str,
+        token: &str,
+        context_id: &str,
+        message_id: Option<u64>,
+    ) -> Result<Socket, SaxoError> {
+        let query = match message_id {
+            Some(message_id) => format!("contextId={context_id}&messageid={message_id}"),
+            None => format!("contextId={context_id}"),
+        };
+
+        let mut request = format!("{url}?{query}").into_client_request()?;
+        request.headers_mut().insert(
+            "Authorization",
+            HeaderValue::from_str(format!("BEARER {token}").as_str())
+                .map_err(|err| SaxoError::HTTPError(Box::new(err)))?,
+        );
+
+        let (socket, _) = tokio_tungstenite::connect_async(request).await?;
+        Ok(socket)
+    }
+
+    async fn run(
+        mut socket: Socket,
+        shared: Arc<Shared>,
+        events: mpsc::UnboundedSender<StreamingEvent>,
+    ) -> Result<(), SaxoError> {
+        let result = loop {
+            // Only errors of the socket itself or of its frames are worth reconnecting for
+            let err = match Self::read(socket, &shared, &events).await {
+                Err(err @ (SaxoError::Streaming(_) | SaxoError::Frame(_))) => err,
+                result => break result,
+            };
+            let _ = events.send(StreamingEvent::ConnectionLost(err));
+
+            match Self::reconnect(&shared, &events).await {
+                Ok(reconnected) => socket = reconnected,
+                Err(err) => break Err(err),
+            }
+        };
+
+        // Close every subscription channel, as no more messages will arrive
+        shared.lock().clear();
+
+        result
+    }
+
+    async fn read(
+        mut socket: Socket,
+        shared: &Arc<Shared>,
+        events: &mpsc::UnboundedSender<StreamingEvent>,
+    ) -> Result<(), SaxoError> {
+        let mut keep_alive = tokio::time::interval(KEEP_ALIVE_INTERVAL);
+        keep_alive.tick().await;
+
+        loop {
+            tokio::select! {
+                _ = keep_alive.tick() => {
+                    socket.send(WsMessage::Ping(Vec::new())).await?;
+                    shared.spawn(Self::authorize(shared.clone(), events.clone()));
+                }
+                frame = socket.next() => match frame {
+                    Some(Ok(WsMessage::Binary(data))) => Self::dispatch(shared, events, &data)?,
+                    Some(Ok(WsMessage::Close(_))) => return Ok(()),
+                    // Pings are answered by tungstenite itself
+                    Some(Ok(_)) => {}
+                    Some(Err(err)) => return Err(err.into()),
+                    None => return Err(Error::Protocol(ProtocolError::ResetWithoutClosingHandshake).into()),
+                },
+            }
+        }
+    }
+
+    /// Reestablishes the connection, resuming the stream from the last received message when
+    /// possible. Otherwise a new context id is used, and every subscription is recreated.
+    async fn reconnect(
+        shared: &Arc<Shared>,
+        events: &mpsc::UnboundedSender<StreamingEvent>,
+    ) -> Result<Socket, SaxoError> {
+        let policy = &shared.policy;
+        let mut delay = policy.initial_delay;
+        let mut attempts = 0;
+
+        loop {
+            tokio::time::sleep(delay).await;
+            attempts += 1;
+
+            // The token may have expired while the connection was open
+            let token = match shared.token.access_token().await {
+                Ok(token) => token,
+                Err(err) if policy.max_attempts.is_some_and(|max| attempts >= max) => {
+                    return Err(err)
+                }
+                Err(_) => {
+                    delay = (delay * 2).min(policy.max_delay);
+                    continue;
+                }
+            };
+
+            let (context_id, last_message_id) = {
+                let session = shared.session();
+                (session.context_id.clone(), session.last_message_id)
+            };
+
+            if let (Some(url), Some(message_id)) = (shared.reconnect_url(), last_message_id) {
+                if let Ok(socket) = Self::open(&url, &token, &context_id, Some(message_id)).await {
+                    shared.session().token = token;
+                    let _ = events.send(StreamingEvent::Reconnected {
+                        context_id,
+                        resumed: true,
+                    });
+                    return Ok(socket);
+                }
+            }
+
+            let reconnects = shared.session().reconnects + 1;
+            let context_id = format!("{}-{reconnects}", shared.base_context_id);
+            match Self::open(&shared.url, &token, &context_id, None).await {
+                Ok(socket) => {
+                    *shared.session() = Session {
+                        context_id: context_id.clone(),
+                        token,
+                        last_message_id: None,
+                        reconnects,
+                    };
+
+                    let subscriptions = shared
+                        .lock()
+                        .values()
+                        .filter_map(|entry| {
+                            Some(Recreation {
+                                reference_id: entry.reference_id.clone(),
+                                previous: None,
+                                subscription: entry.subscription.clone()?,
+                            })
+                        })
+                        .collect();
+                    let _ = events.send(StreamingEvent::Reconnected {
+                        context_id,
+                        resumed: false,
+                    });
+                    shared.spawn(Self::recreate(
+                        shared.clone(),
+                        events.clone(),
+                        subscriptions,
+                    ));
+                    return Ok(socket);
+                }
+                Err(err) if policy.max_attempts.is_some_and(|max| attempts >= max) => {
+                    return Err(err)
+                }
+                Err(_) => delay = (delay * 2).min(policy.max_delay),
+            }
+        }
+    }
+
+    /// Authorizes the context with the token of the provider if it has been refreshed since the
+    /// connection was opened, as the server drops the connection when its token expires.
+    async fn authorize(shared: Arc<Shared>, events: mpsc::UnboundedSender<StreamingEvent>) {
+        let token = match shared.token.access_token().await {
+            Ok(token) => token,
+            Err(err) => {
+                let _ = events.send(StreamingEvent::AuthorizationFailed(err));
+                return;
+            }
+        };
+        if shared.session().token == token {
+            return;
+        }
+
+        let context_id = shared.context_id();
+        match shared.subscriber.authorize(&context_id).await {
+            Ok(()) => shared.session().token = token,
+            Err(err) => {
+                let _ = events.send(StreamingEvent::AuthorizationFailed(err));
+            }
+        }
+    }
+
+    fn dispatch(
+        shared: &Arc<Shared>,
+        events: &mpsc::UnboundedSender<StreamingEvent>,
+        data: &[u8],
+    ) -> Result<(), SaxoError> {
+        // Messages following an ill-formed one cannot be located, so the rest of the frame has to
+        // be sent again by the server
+        for message in frame::decode(data) {
+            let message = message.map_err(SaxoError::Frame)?;
+            shared.session().last_message_id = Some(message.message_id);
+
+            if ControlMessage::is_control(message.reference_id) {
+                match ControlMessage::parse(message.payload.bytes()) {
+                    Ok(controls) => {
+                        for control in controls {
+                            Self::handle_control(shared, events, control)?;
+                        }
+                    }
+                    Err(err) => {
+                        let _ = events.send(StreamingEvent::InvalidControlMessage(err.into()));
+                    }
+                }
+                continue;
+            }
+
+            let mut entries = shared.lock();
+            let closed = entries.get_mut(message.reference_id).map(|entry| {
+                entry.status = SubscriptionStatus::Alive;
+                match &mut entry.pending {
+                    Some(pending) => {
+                        pending.push(message.into());
+                        false
+                    }
+                    None => entry
+                        .sender
+                        .send(SubscriptionUpdate::Delta(message.into()))
+                        .is_err(),
+                }
+            });
+
+            if closed == Some(true) {
+                entries.retain(|_, entry| !entry.sender.is_closed());
+            }
+        }
+
+        Ok(())
+    }
+
+    fn handle_control(
+        shared: &Arc<Shared>,
+        events: &mpsc::UnboundedSender<StreamingEvent>,
+        control: ControlMessage,
+    ) -> Result<(), SaxoError> {
+        match control {
+            ControlMessage::Heartbeat { heartbeats } => {
+                let mut entries = shared.lock();
+                for heartbeat in heartbeats {
+                    let reference_id = match entries.get_mut(&heartbeat.originating_reference_id) {
+                        Some(entry) => {
+                            entry.status = heartbeat.reason.into();
+                            entry.reference_id.clone()
+                        }
+                        None => heartbeat.originating_reference_id,
+                    };
+                    let _ = events.send(StreamingEvent::Heartbeat {
+                        reference_id,
+                        reason: heartbeat.reason,
+                    });
+                }
+            }
+            ControlMessage::ResetSubscriptions {
+                target_reference_ids,
+            } => {
+                // Messages of the previous subscriptions still on their way are dropped from now
+                // on, as they no longer match the reference id of any entry
+                let mut entries = shared.lock();
+                let targets = entries
+                    .iter()
+                    .filter(|(reference_id, entry)| {
+                        entry.subscription.is_some()
+                            && (target_reference_ids.is_empty()
+                                || target_reference_ids.contains(reference_id))
+                    })
+                    .map(|(reference_id, _)| reference_id.clone())
+                    .collect::<Vec<_>>();
+
+                let mut subscriptions = Vec::new();
+                for target in targets {
+                    let Some(mut entry) = entries.remove(&target) else {
+                        continue;
+                    };
+                    entry.resets += 1;
+                    let server_reference_id = format!("{}-{}", entry.reference_id, entry.resets);
+                    let previous = entry.subscription.clone();
+
+                    if let Some(subscription) = &mut entry.subscription {
+                        subscription.reference_id.clone_from(&server_reference_id);
+                        subscriptions.push(Recreation {
+                            reference_id: entry.reference_id.clone(),
+                            previous,
+                            subscription: subscription.clone(),
+                        });
+                    }
+                    entries.insert(server_reference_id, entry);
+                }
+                drop(entries);
+
+                shared.spawn(Self::recreate(
+                    shared.clone(),
+                    events.clone(),
+                    subscriptions,
+                ));
+            }
+            ControlMessage::Disconnect {} => {
+                let _ = events.send(StreamingEvent::Disconnected);
+                return Err(SaxoError::StreamingDisconnected);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Recreates the given subscriptions on the current context id, sending the new snapshots on
+    /// their channels. Subscriptions being reset are deleted first.
+    async fn recreate(
+        shared: Arc<Shared>,
+        events: mpsc::UnboundedSender<StreamingEvent>,
+        recreations: Vec<Recreation>,
+    ) {
+        let context_id = shared.context_id();
+
+        for recreation in recreations {
+            if let Some(previous) = &recreation.previous {
+                // The subscription may already be gone on the server, which is fine
+                let _ = shared.subscriber.unsubscribe(&context_id, previous).await;
+            }
+
+            let reference_id = recreation.reference_id;
+            let event =
+                match Self::subscribe_entry(&shared, &context_id, &recreation.subscription, true)
+                    .await
+                {
+                    Ok(_) => StreamingEvent::SubscriptionReset { reference_id },
+                    Err(error) => StreamingEvent::SubscriptionResetFailed {
+                        reference_id,
+                        error,
+                    },
+                };
+            let _ = events.send(event);
+        }
+    }
+
+    /// Creates a registered subscription on the server. Deltas received before the response
+    /// are held back until then, and if `send_snapshot` is set they follow the new snapshot on
+    /// the channel, so that it doesn't overwrite them.
+    async fn subscribe_entry(
+        shared: &Shared,
+        context_id: &str,
+        subscription: &Subscription,
+        send_snapshot: bool,
+    ) -> Result<SubscriptionResponse, SaxoError> {
+        if let Some(entry) = shared.lock().get_mut(&subscription.reference_id) {
+            entry.pending.get_or_insert_with(Vec::new);
+        }
+
+        let mut result = shared.subscriber.subscribe(context_id, subscription).await;
+
+        if let Some(entry) = shared.lock().get_mut(&subscription.reference_id) {
+            if let (Ok(response), true) = (&mut result, send_snapshot) {
+                entry.status = SubscriptionStatus::Alive;
+                let _ = entry
+                    .sender
+                    .send(SubscriptionUpdate::Snapshot(response.snapshot.take()));
+            }
+            for message in entry.pending.take().unwrap_or_default() {
+                let _ = entry.sender.send(SubscriptionUpdate::Delta(message));
+            }
+        }
+
+        result
+    }
+}
+
+impl Drop for StreamingConnection {
+    fn drop(&mut self) {
+        self.task.abort();
+        for task in self
+            .shared
+            .tasks
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .drain(..)
+        {
+            task.abort();
+        }
+    }
+}
+
+/// Deletes a subscription when dropped, see [`StreamingConnection::guard`].
+#[derive(Debug)]
+pub struct SubscriptionGuard {
+    shared: Weak<Shared>,
+    reference_id: String,
+}
+
+impl Drop for SubscriptionGuard {
+    fn drop(&mut self) {
+        let Some(shared) = self.shared.upgrade() else {
+            return;
+        };
+        let entry = shared.remove(&self.reference_id);
+
+        // The subscription is deleted on the server in the background, if a runtime is running
+        if let (Some(subscription), Ok(runtime)) = (
+            entry.and_then(|entry| entry.subscription),
+            tokio::runtime::Handle::try_current(),
+        ) {
+            runtime.spawn(async move {
+                let context_id = shared.context_id();
+                let _ = shared
+                    .subscriber
+                    .unsubscribe(&context_id, &subscription)
+                    .await;
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::auth::provider::{MockTokenProvider, StaticToken};
+    use crate::error::FrameError;
+    use frame::{encode, Payload};
+    use serde_json::json;
+    use state::SubscriptionState;
+    use subscription::MockSubscriber;
+    use tokio::net::TcpListener;
+    use tokio::sync::{oneshot, Notify};
+    use tokio_tungstenite::tungstenite::handshake::server::{ErrorResponse, Request, Response};
+
+    /// Serves a single connection, sending `messages` once `start` is signalled, and then waits
+    /// for the client to go away.
+    #[allow(clippy::result_large_err)]
+    async fn serve(messages: Vec<WsMessage>) -> (String, oneshot::Sender<()>, JoinHandle<()>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("ws://{}/connect", listener.local_addr().unwrap());
+        let (start, started) = oneshot::channel();
+
+        let server = tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let mut socket = tokio_tungstenite::accept_hdr_async(
+                stream,
+                |request: &Request, response: Response| {
+                    assert_eq!(request.uri().query(), Some("contextId=ctx"));
+                    assert_eq!(request.headers()["Authorization"], "BEARER token");
+                    Ok(response)
+                },
+            )
+            .await
+            .unwrap();
+
+            started.await.unwrap();
+            for message in messages {
+                socket.send(message).await.unwrap();
+            }
+            while let Some(Ok(_)) = socket.next().await {}
+        });
+
+        (url, start, server)
+    }
+
+    fn json_frame(message_id: u64, reference_id: &str, payload: &serde_json::Value) -> Vec<u8> {
+        encode(
+            message_id,
+            reference_id,
+            Payload::Json(payload.to_string().as_bytes()),
+        )
+    }
+
+    async fn connect(url: &str, subscriber: MockSubscriber) -> StreamingConnection {
+        StreamingConnection::connect(
+            url,
+            Arc::new(StaticToken::new("token")),
+            "ctx",
+            Arc::new(subscriber),
+        )
+        .await
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_connect_and_dispatch() {
+        let mut frame = json_frame(1, "foo", &json!({ "Foo": "Bar" }));
+        frame.extend(json_frame(2, "unknown", &json!({})));
+        frame.extend(json_frame(3, "foo", &json!({ "Foo": "Baz" })));
+        let (url, start, server) =
+            serve(vec![WsMessage::Binary(frame), WsMessage::Close(None)]).await;
+
+        let mut connection = connect(&url, MockSubscriber::new()).await;
+        assert_eq!(connection.context_id(), "ctx");
+        let mut foo = connection.subscribe("foo");
+        start.send(()).unwrap();
+
+        server.await.unwrap();
+        assert!(connection.closed().await.is_ok());
+
+        let Some(SubscriptionUpdate::Delta(first)) = foo.recv().await else {
+            panic!("expected delta");
+        };
+        assert_eq!(first.message_id, 1);
+        assert_eq!(
+            first.json::<serde_json::Value>().unwrap()["Foo"],
+            json!("Bar")
+        );
+        assert!(matches!(
+            foo.recv().await,
+            Some(SubscriptionUpdate::Delta(Message { message_id: 3, .. }))
+        ));
+        assert!(foo.recv().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_heartbeat() {
+        let heartbeat = json!([{
+            "ReferenceId": "_heartbeat",
+            "Heartbeats": [{ "OriginatingReferenceId": "foo", "Reason": "NoNewData" }]
+        }]);
+        let mut frame = json_frame(1, "_heartbeat", &heartbeat);
+        frame.extend(json_frame(2, "bar", &json!({})));
+        let (url, start, _server) = serve(vec![WsMessage::Binary(frame)]).await;
+
+        let mut connection = connect(&url, MockSubscriber::new()).await;
+        let _foo = connection.subscribe("foo");
+        let mut bar = connection.subscribe("bar");
+        assert_eq!(connection.status("foo"), Some(SubscriptionStatus::Alive));
+        start.send(()).unwrap();
+
+        assert!(matches!(
+            connection.next_event().await,
+            Some(StreamingEvent::Heartbeat { reference_id, reason: HeartbeatReason::NoNewData })
+                if reference_id == "foo"
+        ));
+        bar.recv().await.unwrap();
+        assert_eq!(
+            connection.status("foo"),
+            Some(SubscriptionStatus::NoNewData)
+        );
+        assert_eq!(connection.status("bar"), Some(SubscriptionStatus::Alive));
+    }
+
+    #[tokio::test]
+    async fn test_invalid_control_message() {
+        let reset = json!({ "ReferenceId": "_resetsubscriptions", "TargetReferenceIds": "foo" });
+        let mut frame = json_frame(1, "_resetsubscriptions", &reset);
+        frame.extend(json_frame(2, "foo", &json!({})));
+        let (url, start, _server) = serve(vec![WsMessage::Binary(frame)]).await;
+
+        let mut connection = connect(&url, MockSubscriber::new()).await;
+        let mut foo = connection.subscribe("foo");
+        start.send(()).unwrap();
+
+        assert!(matches!(
+            connection.next_event().await,
+            Some(StreamingEvent::InvalidControlMessage(SaxoError::Json(_)))
+        ));
+        assert!(matches!(
+            foo.recv().await,
+            Some(SubscriptionUpdate::Delta(Message { message_id: 2, .. }))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_reset_subscriptions() {
+        let reset = json!({ "ReferenceId": "_resetsubscriptions", "TargetReferenceIds": ["foo"] });
+        let (url, start, _server) = serve(vec![WsMessage::Binary(json_frame(
+            1,
+            "_resetsubscriptions",
+            &reset,
+        ))])
+        .await;
+
+        let mut subscriber = MockSubscriber::new();
+        let mut snapshot = 0;
+        subscriber
+            .expect_subscribe()
+            .times(3)
+            .returning(move |context_id, subscription| {
+                assert_eq!(context_id, "ctx");
+                snapshot += 1;
+                if snapshot == 3 {
+                    assert_eq!(subscription.reference_id, "foo-1");
+                }
+                Ok(SubscriptionResponse {
+                    reference_id: Some(subscription.reference_id.clone()),
+                    snapshot: json!({ "Snapshot": snapshot }),
+                    ..Default::default()
+                })
+            });
+        subscriber
+            .expect_unsubscribe()
+            .once()
+            .withf(|_, subscription| subscription.reference_id == "foo")
+            .returning(|_, _| Ok(()));
+
+        let mut connection = connect(&url, subscriber).await;
+        let (response, mut foo) = connection
+            .create_subscription(Subscription::new("foo/subscriptions", "foo", json!({})))
+            .await
+            .unwrap();
+        assert_eq!(response.snapshot, json!({ "Snapshot": 1 }));
+        let (_, mut bar) = connection
+            .create_subscription(Subscription::new("bar/subscriptions", "bar", json!({})))
+            .await
+            .unwrap();
+        start.send(()).unwrap();
+
+        assert!(matches!(
+            connection.next_event().await,
+            Some(StreamingEvent::SubscriptionReset { reference_id }) if reference_id == "foo"
+        ));
+        assert_eq!(
+            foo.recv().await,
+            Some(SubscriptionUpdate::Snapshot(json!({ "Snapshot": 3 })))
+        );
+        assert!(bar.try_recv().is_err());
+    }
+
+    /// Subscriber holding back the response of `subscribe` until it is released.
+    #[derive(Default)]
+    struct DelayedSubscriber {
+        subscribed: Notify,
+        release: Notify,
+    }
+
+    #[async_trait::async_trait]
+    impl Subscriber for DelayedSubscriber {
+        async fn subscribe(
+            &self,
+            _context_id: &str,
+            _subscription: &Subscription,
+        ) -> Result<SubscriptionResponse, SaxoError> {
+            self.subscribed.notify_one();
+            self.release.notified().await;
+            Ok(SubscriptionResponse {
+                snapshot: json!({ "Foo": 1 }),
+                ..Default::default()
+            })
+        }
+
+        async fn unsubscribe(
+            &self,
+            _context_id: &str,
+            _subscription: &Subscription,
+        ) -> Result<(), SaxoError> {
+            Ok(())
+        }
+
+        async fn authorize(&self, _context_id: &str) -> Result<(), SaxoError> {
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_reset_delta_before_snapshot() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("ws://{}/connect", listener.local_addr().unwrap());
+        let (start, started) = oneshot::channel();
+        let subscriber = Arc::new(DelayedSubscriber::default());
+
+        let server_subscriber = subscriber.clone();
+        let _server = tokio::spawn(async move {
+            let mut socket = accept(&listener, "/connect", "contextId=ctx")
+                .await
+                .unwrap();
+            started.await.unwrap();
+            let reset =
+                json!({ "ReferenceId": "_resetsubscriptions", "TargetReferenceIds": ["foo"] });
+            let frame = json_frame(1, "_resetsubscriptions", &reset);
+            socket.send(WsMessage::Binary(frame)).await.unwrap();
+
+            // A delta of the previous subscription, and one of the new subscription arriving
+            // before its snapshot, followed by a heartbeat telling that they have been received
+            server_subscriber.subscribed.notified().await;
+            let heartbeat = json!([{
+                "ReferenceId": "_heartbeat",
+                "Heartbeats": [{ "OriginatingReferenceId": "foo-1", "Reason": "NoNewData" }]
+            }]);
+            let mut frame = json_frame(2, "foo", &json!({ "Foo": 9 }));
+            frame.extend(json_frame(3, "foo-1", &json!({ "Foo": 2 })));
+            frame.extend(json_frame(4, "_heartbeat", &heartbeat));
+            socket.send(WsMessage::Binary(frame)).await.unwrap();
+            while let Some(Ok(_)) = socket.next().await {}
+        });
+
+        let mut connection = StreamingConnection::connect(
+            &url,
+            Arc::new(StaticToken::new("token")),
+            "ctx",
+            subscriber.clone(),
+        )
+        .await
+        .unwrap();
+        let mut foo = connection.register(
+            "foo",
+            Some(Subscription::new("foo/subscriptions", "foo", json!({}))),
+        );
+        start.send(()).unwrap();
+
+        assert!(matches!(
+            connection.next_event().await,
+            Some(StreamingEvent::Heartbeat { reference_id, .. }) if reference_id == "foo"
+        ));
+        subscriber.release.notify_one();
+        assert!(matches!(
+            connection.next_event().await,
+            Some(StreamingEvent::SubscriptionReset { reference_id }) if reference_id == "foo"
+        ));
+
+        let mut state = SubscriptionState::<serde_json::Value>::new(json!({ "Foo": 0 }));
+        state.update(foo.recv().await.unwrap()).unwrap();
+        let delta = foo.recv().await.unwrap();
+        assert!(matches!(
+            &delta,
+            SubscriptionUpdate::Delta(Message { message_id: 3, .. })
+        ));
+        state.update(delta).unwrap();
+        assert_eq!(state.json(), &json!({ "Foo": 2 }));
+        assert!(foo.try_recv().is_err());
+        assert_eq!(connection.status("foo"), Some(SubscriptionStatus::Alive));
+    }
+
+    #[tokio::test]
+    async fn test_drop_aborts_tasks() {
+        let (url, _start, _server) = serve(Vec::new()).await;
+        let subscriber = Arc::new(DelayedSubscriber::default());
+
+        let connection = StreamingConnection::connect(
+            &url,
+            Arc::new(StaticToken::new("token")),
+            "ctx",
+            subscriber.clone(),
+        )
+        .await
+        .unwrap();
+        let _foo = connection.register(
+            "foo",
+            Some(Subscription::new("foo/subscriptions", "foo", json!({}))),
+        );
+        let (events, _events_receiver) = mpsc::unbounded_channel();
+        let recreations = vec![Recreation {
+            reference_id: "foo".to_owned(),
+            previous: None,
+            subscription: Subscription::new("foo/subscriptions", "foo", json!({})),
+        }];
+        connection.shared.spawn(StreamingConnection::recreate(
+            connection.shared.clone(),
+            events,
+            recreations,
+        ));
+        subscriber.subscribed.notified().await;
+
+        // The pending recreation holds the last other reference to the shared state
+        let shared = Arc::downgrade(&connection.shared);
+        drop(connection);
+        tokio::task::yield_now().await;
+        assert!(shared.upgrade().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_subscription_guard() {
+        let (url, _start, _server) = serve(Vec::new()).await;
+
+        let mut subscriber = MockSubscriber::new();
+        subscriber
+            .expect_subscribe()
+            .once()
+            .returning(|_, _| Ok(SubscriptionResponse::default()));
+        let (unsubscribed, mut unsubscribed_receiver) = mpsc::unbounded_channel();
+        subscriber
+            .expect_unsubscribe()
+            .once()
+            .withf(|context_id, subscription| {
+                context_id == "ctx" && subscription.reference_id == "foo"
+            })
+            .returning(move |_, _| {
+                unsubscribed.send(()).unwrap();
+                Ok(())
+            });
+
+        let connection = connect(&url, subscriber).await;
+        let (_, mut foo) = connection
+            .create_subscription(Subscription::new("foo/subscriptions", "foo", json!({})))
+            .await
+            .unwrap();
+        let guard = connection.guard("foo");
+        assert_eq!(connection.status("foo"), Some(SubscriptionStatus::Alive));
+
+        drop(guard);
+        assert_eq!(connection.status("foo"), None);
+        assert!(foo.recv().await.is_none());
+        unsubscribed_receiver.recv().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_authorize_refreshed_token() {
+        let (url, _start, _server) = serve(Vec::new()).await;
+
+        let mut token = MockTokenProvider::new();
+        let mut sequence = mockall::Sequence::new();
+        token
+            .expect_access_token()
+            .once()
+            .in_sequence(&mut sequence)
+            .returning(|| Ok("token".to_owned()));
+        token
+            .expect_access_token()
+            .times(2)
+            .in_sequence(&mut sequence)
+            .returning(|| Ok("refreshed".to_owned()));
+        let mut subscriber = MockSubscriber::new();
+        subscriber
+            .expect_authorize()
+            .once()
+            .withf(|context_id| context_id == "ctx")
+            .returning(|_| Ok(()));
+
+        let connection =
+            StreamingConnection::connect(&url, Arc::new(token), "ctx", Arc::new(subscriber))
+                .await
+                .unwrap();
+        let (events, mut events_receiver) = mpsc::unbounded_channel();
+
+        // The context is only authorized again once the token changes
+        StreamingConnection::authorize(connection.shared.clone(), events.clone()).await;
+        StreamingConnection::authorize(connection.shared.clone(), events).await;
+        assert_eq!(connection.shared.session().token, "refreshed");
+        assert!(events_receiver.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_disconnect() {
+        let disconnect = json!([{ "ReferenceId": "_disconnect" }]);
+        let (url, start, _server) = serve(vec![WsMessage::Binary(json_frame(
+            1,
+            "_disconnect",
+            &disconnect,
+        ))])
+        .await;
+
+        let mut connection = connect(&url, MockSubscriber::new()).await;
+        let mut foo = connection.subscribe("foo");
+        start.send(()).unwrap();
+
+        assert!(matches!(
+            connection.next_event().await,
+            Some(StreamingEvent::Disconnected)
+        ));
+        assert!(matches!(
+            connection.closed().await,
+            Err(SaxoError::StreamingDisconnected)
+        ));
+        assert!(foo.recv().await.is_none());
+    }
+
+    fn policy() -> ReconnectPolicy {
+        ReconnectPolicy {
+            initial_delay: Duration::from_millis(10),
+            max_delay: Duration::from_millis(10),
+            max_attempts: Some(3),
+        }
+    }
+
+    /// Accepts the next connection if it is made to `path` with exactly `query`, rejecting it
+    /// otherwise.
+    #[allow(clippy::result_large_err)]
+    async fn accept(
+        listener: &TcpListener,
+        path: &'static str,
+        query: &'static str,
+    ) -> Option<WebSocketStream<TcpStream>> {
+        let (stream, _) = listener.accept().await.unwrap();
+        tokio_tungstenite::accept_hdr_async(stream, |request: &Request, response: Response| {
+            if request.uri().path() == path && request.uri().query() == Some(query) {
+                Ok(response)
+            } else {
+                let mut error = ErrorResponse::new(None);
+                *error.status_mut() = http::StatusCode::NOT_FOUND;
+                Err(error)
+            }
+        })
+        .await
+        .ok()
+    }
+
+    #[tokio::test]
+    async fn test_reconnect_resume() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("ws://{}/connect", listener.local_addr().unwrap());
+        let (start, started) = oneshot::channel();
+
+        let _server = tokio::spawn(async move {
+            let mut socket = accept(&listener, "/connect", "contextId=ctx")
+                .await
+                .unwrap();
+            started.await.unwrap();
+            let frame = json_frame(5, "foo", &json!({ "Foo": 1 }));
+            socket.send(WsMessage::Binary(frame)).await.unwrap();
+            // Drop the connection without a closing handshake
+            drop(socket);
+
+            let mut socket = accept(&listener, "/reconnect", "contextId=ctx&messageid=5")
+                .await
+                .unwrap();
+            let frame = json_frame(6, "foo", &json!({ "Foo": 2 }));
+            socket.send(WsMessage::Binary(frame)).await.unwrap();
+            while let Some(Ok(_)) = socket.next().await {}
+        });
+
+        let mut connection = StreamingConnection::connect_with_policy(
+            &url,
+            Arc::new(StaticToken::new("token")),
+            "ctx",
+            Arc::new(MockSubscriber::new()),
+            policy(),
+        )
+        .await
+        .unwrap();
+        let mut foo = connection.subscribe("foo");
+        start.send(()).unwrap();
+
+        assert!(matches!(
+            foo.recv().await,
+            Some(SubscriptionUpdate::Delta(Message { message_id: 5, .. }))
+        ));
+        assert!(matches!(
+            connection.next_event().await,
+            Some(StreamingEvent::ConnectionLost(SaxoError::Streaming(_)))
+        ));
+        assert!(matches!(
+            connection.next_event().await,
+            Some(StreamingEvent::Reconnected { context_id, resumed: true }) if context_id == "ctx"
+        ));
+        assert!(matches!(
+            foo.recv().await,
+            Some(SubscriptionUpdate::Delta(Message { message_id: 6, .. }))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_reconnect_ill_formed_frame() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("ws://{}/connect", listener.local_addr().unwrap());
+        let (start, started) = oneshot::channel();
+
+        let _server = tokio::spawn(async move {
+            let mut socket = accept(&listener, "/connect", "contextId=ctx")
+                .await
+                .unwrap();
+            started.await.unwrap();
+            let mut frame = json_frame(5, "foo", &json!({ "Foo": 1 }));
+            let next = json_frame(6, "foo", &json!({ "Foo": 2 }));
+            frame.extend(&next[..next.len() - 1]);
+            socket.send(WsMessage::Binary(frame)).await.unwrap();
+
+            let mut socket = accept(&listener, "/reconnect", "contextId=ctx&messageid=5")
+                .await
+                .unwrap();
+            socket.send(WsMessage::Binary(next)).await.unwrap();
+            while let Some(Ok(_)) = socket.next().await {}
+        });
+
+        let mut connection = StreamingConnection::connect_with_policy(
+            &url,
+            Arc::new(StaticToken::new("token")),
+            "ctx",
+            Arc::new(MockSubscriber::new()),
+            policy(),
+        )
+        .await
+        .unwrap();
+        let mut foo = connection.subscribe("foo");
+        start.send(()).unwrap();
+
+        assert!(matches!(
+            foo.recv().await,
+            Some(SubscriptionUpdate::Delta(Message { message_id: 5, .. }))
+        ));
+        assert!(matches!(
+            connection.next_event().await,
+            Some(StreamingEvent::ConnectionLost(SaxoError::Frame(
+                FrameError::Truncated(_)
+            )))
+        ));
+        assert!(matches!(
+            connection.next_event().await,
+            Some(StreamingEvent::Reconnected { resumed: true, .. })
+        ));
+        assert!(matches!(
+            foo.recv().await,
+            Some(SubscriptionUpdate::Delta(Message { message_id: 6, .. }))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_reconnect_new_context() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("ws://{}/connect", listener.local_addr().unwrap());
+        let (start, started) = oneshot::channel();
+
+        let _server = tokio::spawn(async move {
+            let mut socket = accept(&listener, "/connect", "contextId=ctx")
+                .await
+                .unwrap();
+            started.await.unwrap();
+            let frame = json_frame(5, "foo", &json!({ "Foo": 1 }));
+            socket.send(WsMessage::Binary(frame)).await.unwrap();
+            drop(socket);
+
+            // The stream cannot be resumed
+            assert!(accept(&listener, "/reconnect", "").await.is_none());
+
+            let mut socket = accept(&listener, "/connect", "contextId=ctx-1")
+                .await
+                .unwrap();
+            while let Some(Ok(_)) = socket.next().await {}
+        });
+
+        let mut subscriber = MockSubscriber::new();
+        subscriber
+            .expect_subscribe()
+            .times(2)
+            .returning(|context_id, _| {
+                Ok(SubscriptionResponse {
+                    snapshot: json!({ "ContextId": context_id }),
+                    ..Default::default()
+                })
+            });
+
+        let mut connection = StreamingConnection::connect_with_policy(
+            &url,
+            Arc::new(StaticToken::new("token")),
+            "ctx",
+            Arc::new(subscriber),
+            policy(),
+        )
+        .await
+        .unwrap();
+        let (_, mut foo) = connection
+            .create_subscription(Subscription::new("foo/subscriptions", "foo", json!({})))
+            .await
+            .unwrap();
+        start.send(()).unwrap();
+
+        assert!(matches!(
+            foo.recv().await,
+            Some(SubscriptionUpdate::Delta(Message { message_id: 5, .. }))
+        ));
+        assert!(matches!(
+            connection.next_event().await,
+            Some(StreamingEvent::ConnectionLost(_))
+        ));
+        assert!(matches!(
+            connection.next_event().await,
+            Some(StreamingEvent::Reconnected { context_id, resumed: false }) if context_id == "ctx-1"
+        ));
+        assert!(matches!(
+            connection.next_event().await,
+            Some(StreamingEvent::SubscriptionReset { reference_id }) if reference_id == "foo"
+        ));
+        assert_eq!(
+            foo.recv().await,
+            Some(SubscriptionUpdate::Snapshot(
+                json!({ "ContextId": "ctx-1" })
+            ))
+        );
+        assert_eq!(connection.context_id(), "ctx-1");
+    }
+}
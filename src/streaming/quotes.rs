@@ -0,0 +1,258 @@
+//! Stream of quotes merged from the snapshot and deltas of a price subscription, see
+//! <https://www.developer.saxo/openapi/learn/pricing>
+
+use super::state::SubscriptionState;
+use super::subscription::SubscriptionUpdate;
+use super::SubscriptionGuard;
+use crate::error::SaxoError;
+use crate::messages::trading::infoprices::Quote;
+
+use futures_util::Stream;
+use serde::Deserialize;
+use serde_json::Value;
+use tokio::sync::mpsc::UnboundedReceiver;
+
+use std::collections::VecDeque;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+/// Field identifying an instrument in price snapshots and deltas.
+const UIC: &str = "Uic";
+
+/// Up-to-date quote of an instrument.
+#[derive(Debug, Clone, PartialEq)]
+pub struct QuoteUpdate {
+    pub uic: i32,
+    pub quote: Quote,
+}
+
+/// Stream yielding the merged quote of every instrument whose price changed, starting with the
+/// quotes of the initial snapshot. The subscription is deleted when the stream is dropped.
+#[derive(Debug)]
+pub struct QuoteStream {
+    receiver: UnboundedReceiver<SubscriptionUpdate>,
+    state: SubscriptionState<Value>,
+    /// Whether the subscription is for a list of instruments, rather than a single one
+    list: bool,
+    pending: VecDeque<Result<QuoteUpdate, SaxoError>>,
+    _guard: Option<SubscriptionGuard>,
+}
+
+impl QuoteStream {
+    /// Stream of a `trade/v1/prices` subscription for a single instrument.
+    #[must_use]
+    pub fn single(
+        snapshot: Value,
+        receiver: UnboundedReceiver<SubscriptionUpdate>,
+        guard: Option<SubscriptionGuard>,
+    ) -> Self {
+        Self::new(SubscriptionState::new(snapshot), false, receiver, guard)
+    }
+
+    /// Stream of a `trade/v1/infoprices` subscription for a list of instruments.
+    #[must_use]
+    pub fn list(
+        snapshot: Value,
+        receiver: UnboundedReceiver<SubscriptionUpdate>,
+        guard: Option<SubscriptionGuard>,
+    ) -> Self {
+        Self::new(
+            SubscriptionState::keyed(snapshot, UIC),
+            true,
+            receiver,
+            guard,
+        )
+    }
+
+    fn new(
+        state: SubscriptionState<Value>,
+        list: bool,
+        receiver: UnboundedReceiver<SubscriptionUpdate>,
+        guard: Option<SubscriptionGuard>,
+    ) -> Self {
+        let mut stream = QuoteStream {
+            receiver,
+            state,
+            list,
+            pending: VecDeque::new(),
+            _guard: guard,
+        };
+        stream.push_all();
+        stream
+    }
+
+    /// Merges an update into the state, queuing the quotes of the instruments it changed.
+    fn update(&mut self, update: SubscriptionUpdate) {
+        let delta = match update {
+            SubscriptionUpdate::Snapshot(snapshot) => {
+                if let Err(err) = self.state.update(SubscriptionUpdate::Snapshot(snapshot)) {
+                    return self.pending.push_back(Err(err.into()));
+                }
+                return self.push_all();
+            }
+            SubscriptionUpdate::Delta(message) => match message.json::<Value>() {
+                Ok(delta) => delta,
+                Err(err) => return self.pending.push_back(Err(err.into())),
+            },
+        };
+
+        if self.list {
+            let elements = match &delta {
+                Value::Array(elements) => elements.as_slice(),
+                Value::Object(delta) => delta
+                    .get("Data")
+                    .and_then(Value::as_array)
+                    .map_or(&[][..], Vec::as_slice),
+                _ => &[],
+            };
+            let uics: Vec<Value> = elements
+                .iter()
+                .filter_map(|element| element.get(UIC).cloned())
+                .collect();
+
+            self.state.apply(delta);
+            let updates: Vec<_> = uics
+                .iter()
+                .filter_map(|uic| {
+                    self.elements()
+                        .find(|element| element.get(UIC) == Some(uic))
+                        .map(Self::quote)
+                })
+                .collect();
+            self.pending.extend(updates);
+        } else {
+            self.state.apply(delta);
+            let update = Self::quote(self.state.json());
+            self.pending.push_back(update);
+        }
+    }
+
+    /// Queues the quotes of all instruments of the state.
+    fn push_all(&mut self) {
+        let updates: Vec<_> = if self.list {
+            self.elements().map(Self::quote).collect()
+        } else {
+            vec![Self::quote(self.state.json())]
+        };
+        self.pending.extend(updates);
+    }
+
+    fn elements(&self) -> impl Iterator<Item = &Value> {
+        self.state
+            .json()
+            .get("Data")
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+    }
+
+    fn quote(element: &Value) -> Result<QuoteUpdate, SaxoError> {
+        let uic = serde_json::from_value(element.get(UIC).cloned().unwrap_or_default())?;
+        let quote = match element.get("Quote") {
+            Some(quote) => Quote::deserialize(quote)?,
+            None => Quote::default(),
+        };
+
+        Ok(QuoteUpdate { uic, quote })
+    }
+}
+
+impl Stream for QuoteStream {
+    type Item = Result<QuoteUpdate, SaxoError>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        loop {
+            if let Some(update) = self.pending.pop_front() {
+                return Poll::Ready(Some(update));
+            }
+            match self.receiver.poll_recv(cx) {
+                Poll::Ready(Some(update)) => self.update(update),
+                Poll::Ready(None) => return Poll::Ready(None),
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::streaming::frame::PayloadFormat;
+    use crate::streaming::Message;
+    use futures_util::StreamExt;
+    use serde_json::json;
+    use tokio::sync::mpsc;
+
+    fn delta(payload: &Value) -> SubscriptionUpdate {
+        SubscriptionUpdate::Delta(Message {
+            message_id: 1,
+            reference_id: "prices".to_owned(),
+            format: PayloadFormat::Json,
+            payload: payload.to_string().into_bytes(),
+        })
+    }
+
+    async fn next(stream: &mut QuoteStream) -> QuoteUpdate {
+        stream.next().await.unwrap().unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_single_quote_stream() {
+        let (sender, receiver) = mpsc::unbounded_channel();
+        let snapshot = json!({ "Quote": { "Ask": 1.2, "Bid": 1.1 }, "Uic": 21 });
+        let mut stream = QuoteStream::single(snapshot, receiver, None);
+
+        assert_eq!(next(&mut stream).await.quote.ask, Some(1.2));
+
+        sender
+            .send(delta(&json!({ "Quote": { "Bid": 1.15 } })))
+            .unwrap();
+        let update = next(&mut stream).await;
+        assert_eq!(update.uic, 21);
+        assert_eq!(update.quote.ask, Some(1.2));
+        assert_eq!(update.quote.bid, Some(1.15));
+
+        sender
+            .send(SubscriptionUpdate::Delta(Message {
+                message_id: 2,
+                reference_id: "prices".to_owned(),
+                format: PayloadFormat::Json,
+                payload: b"{".to_vec(),
+            }))
+            .unwrap();
+        assert!(matches!(stream.next().await, Some(Err(SaxoError::Json(_)))));
+
+        drop(sender);
+        assert!(stream.next().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_list_quote_stream() {
+        let (sender, receiver) = mpsc::unbounded_channel();
+        let snapshot = json!({
+            "Data": [
+                { "Quote": { "Mid": 1.1 }, "Uic": 21 },
+                { "Quote": { "Mid": 180.5 }, "Uic": 211 }
+            ]
+        });
+        let mut stream = QuoteStream::list(snapshot, receiver, None);
+
+        assert_eq!(next(&mut stream).await.uic, 21);
+        assert_eq!(next(&mut stream).await.uic, 211);
+
+        sender
+            .send(delta(&json!([{ "Quote": { "Mid": 181.0 }, "Uic": 211 }])))
+            .unwrap();
+        sender
+            .send(SubscriptionUpdate::Snapshot(json!({
+                "Data": [{ "Quote": { "Mid": 1.2 }, "Uic": 21 }]
+            })))
+            .unwrap();
+
+        let update = next(&mut stream).await;
+        assert_eq!((update.uic, update.quote.mid), (211, Some(181.0)));
+        let update = next(&mut stream).await;
+        assert_eq!((update.uic, update.quote.mid), (21, Some(1.2)));
+    }
+}
@@ -0,0 +1,274 @@
+//! Merging of subscription snapshots and deltas, see
+//! <https://www.developer.saxo/openapi/learn/streaming>
+//!
+//! The snapshot returned when creating a subscription is followed by deltas only holding the
+//! fields that changed. Since every field of a `saxo_response!` struct is an `Option`, the merged
+//! value deserializes into the same struct as the snapshot.
+
+use super::subscription::SubscriptionUpdate;
+use super::Message;
+
+use serde::de::DeserializeOwned;
+use serde_json::{Map, Value};
+
+use std::marker::PhantomData;
+
+/// Marker sent in list deltas for elements that have been removed.
+const META_DELETED: &str = "__meta_deleted";
+
+/// Up-to-date value of a subscription of type `T`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SubscriptionState<T> {
+    value: Value,
+    key: Option<String>,
+    marker: PhantomData<T>,
+}
+
+impl<T: DeserializeOwned> SubscriptionState<T> {
+    /// Creates the state from the `Snapshot` of a subscription response.
+    #[must_use]
+    pub fn new(snapshot: Value) -> Self {
+        SubscriptionState {
+            value: snapshot,
+            key: None,
+            marker: PhantomData,
+        }
+    }
+
+    /// Creates the state of a list subscription, whose `Data` elements are identified by the
+    /// `key` field, e.g. `PositionId`.
+    #[must_use]
+    pub fn keyed(snapshot: Value, key: &str) -> Self {
+        SubscriptionState {
+            value: snapshot,
+            key: Some(key.to_owned()),
+            marker: PhantomData,
+        }
+    }
+
+    /// Merges a delta into the state.
+    pub fn apply(&mut self, delta: Value) {
+        match &self.key {
+            Some(key) => merge_list(&mut self.value, delta, key),
+            None => merge(&mut self.value, delta),
+        }
+    }
+
+    /// Merges the JSON payload of a streaming message into the state.
+    pub fn apply_message(&mut self, message: &Message) -> serde_json::Result<()> {
+        self.apply(message.json()?);
+        Ok(())
+    }
+
+    /// Applies an update received on the channel of the subscription. A new snapshot replaces
+    /// the state altogether.
+    pub fn update(&mut self, update: SubscriptionUpdate) -> serde_json::Result<()> {
+        match update {
+            SubscriptionUpdate::Snapshot(snapshot) => self.value = snapshot,
+            SubscriptionUpdate::Delta(message) => self.apply_message(&message)?,
+        }
+        Ok(())
+    }
+
+    /// Deserializes the merged state.
+    pub fn get(&self) -> serde_json::Result<T> {
+        T::deserialize(&self.value)
+    }
+
+    /// The merged state as raw JSON.
+    #[must_use]
+    pub fn json(&self) -> &Value {
+        &self.value
+    }
+}
+
+/// Recursively merges `delta` into `target`. Fields missing from the delta are left unchanged,
+/// anything that isn't an object, including arrays, is replaced.
+fn merge(target: &mut Value, delta: Value) {
+    match (target, delta) {
+        (Value::Object(target), Value::Object(delta)) => merge_object(target, delta),
+        (target, delta) => *target = delta,
+    }
+}
+
+fn merge_object(target: &mut Map<String, Value>, delta: Map<String, Value>) {
+    for (field, value) in delta {
+        match target.get_mut(&field) {
+            Some(existing) => merge(existing, value),
+            None => {
+                target.insert(field, value);
+            }
+        }
+    }
+}
+
+/// Merges a list delta into the `Data` array of `target`, matching elements by `key`.
+/// The delta is either the bare array of changed elements or an object with a `Data` array.
+fn merge_list(target: &mut Value, delta: Value, key: &str) {
+    let elements = match delta {
+        Value::Array(elements) => elements,
+        Value::Object(mut delta) => {
+            let elements = match delta.remove("Data") {
+                Some(Value::Array(elements)) => elements,
+                _ => Vec::new(),
+            };
+            if let Value::Object(target) = target {
+                merge_object(target, delta);
+            }
+            elements
+        }
+        delta => return merge(target, delta),
+    };
+
+    if !target.is_object() {
+        *target = Value::Object(Map::new());
+    }
+    let data = target.as_object_mut().map(|target| {
+        target
+            .entry("Data")
+            .or_insert_with(|| Value::Array(Vec::new()))
+    });
+    let Some(Value::Array(data)) = data else {
+        return;
+    };
+
+    for element in elements {
+        let id = element.get(key).cloned();
+        let position = id.as_ref().and_then(|id| {
+            data.iter()
+                .position(|existing| existing.get(key) == Some(id))
+        });
+        let deleted = element.get(META_DELETED) == Some(&Value::Bool(true));
+
+        match (position, deleted) {
+            (Some(position), true) => {
+                data.remove(position);
+            }
+            (Some(position), false) => merge(&mut data[position], element),
+            (None, false) => data.push(element),
+            (None, true) => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::streaming::frame::PayloadFormat;
+    use serde_json::json;
+
+    use messages::{Quote, Response};
+
+    #[allow(dead_code)]
+    mod messages {
+        use crate::{saxo_request, saxo_response};
+
+        saxo_request!("foo/bar/");
+        saxo_response! {
+            bid: f64,
+            ask: f64,
+            quote: Quote
+        }
+        saxo_response! {
+            struct Quote {
+                amount: i32,
+                market_state: String
+            }
+        }
+    }
+
+    #[test]
+    fn test_merge_delta() {
+        let mut state = SubscriptionState::<Response>::new(json!({
+            "Bid": 1.1,
+            "Ask": 1.2,
+            "Quote": { "Amount": 100, "MarketState": "Open" }
+        }));
+
+        state.apply(json!({ "Ask": 1.3, "Quote": { "Amount": 200 } }));
+
+        assert_eq!(
+            state.get().unwrap(),
+            Response {
+                bid: Some(1.1),
+                ask: Some(1.3),
+                quote: Some(Quote {
+                    amount: Some(200),
+                    market_state: Some("Open".to_owned()),
+                }),
+            }
+        );
+    }
+
+    #[test]
+    fn test_merge_delta_missing_from_snapshot() {
+        let mut state = SubscriptionState::<Response>::new(json!({ "Bid": 1.1 }));
+        state.apply(json!({ "Quote": { "MarketState": "Closed" } }));
+
+        let response = state.get().unwrap();
+        assert_eq!(response.bid, Some(1.1));
+        assert_eq!(response.ask, None);
+        assert_eq!(
+            response.quote.unwrap().market_state.as_deref(),
+            Some("Closed")
+        );
+    }
+
+    #[test]
+    fn test_merge_list_delta() {
+        let mut state = SubscriptionState::<Value>::keyed(
+            json!({
+                "__count": 3,
+                "Data": [
+                    { "PositionId": "1", "Amount": 10, "Status": "Open" },
+                    { "PositionId": "2", "Amount": 20, "Status": "Open" },
+                    { "PositionId": "3", "Amount": 30, "Status": "Open" }
+                ]
+            }),
+            "PositionId",
+        );
+
+        state.apply(json!([
+            { "PositionId": "1", "Amount": 15 },
+            { "PositionId": "2", "__meta_deleted": true },
+            { "PositionId": "4", "Amount": 40, "Status": "Open" }
+        ]));
+        state.apply(json!({ "Data": [{ "PositionId": "3", "Status": "Closed" }] }));
+
+        assert_eq!(
+            state.json()["Data"],
+            json!([
+                { "PositionId": "1", "Amount": 15, "Status": "Open" },
+                { "PositionId": "3", "Amount": 30, "Status": "Closed" },
+                { "PositionId": "4", "Amount": 40, "Status": "Open" }
+            ])
+        );
+    }
+
+    #[test]
+    fn test_apply_message() {
+        let mut state = SubscriptionState::<Response>::new(json!({ "Bid": 1.1 }));
+        state
+            .apply_message(&Message {
+                message_id: 1,
+                reference_id: "foo".to_owned(),
+                format: PayloadFormat::Json,
+                payload: b"{\"Bid\":1.5}".to_vec(),
+            })
+            .unwrap();
+
+        assert_eq!(state.get().unwrap().bid, Some(1.5));
+    }
+
+    #[test]
+    fn test_update_snapshot() {
+        let mut state = SubscriptionState::<Response>::new(json!({ "Bid": 1.1, "Ask": 1.2 }));
+        state
+            .update(SubscriptionUpdate::Snapshot(json!({ "Bid": 1.5 })))
+            .unwrap();
+
+        assert_eq!(state.get().unwrap().bid, Some(1.5));
+        assert_eq!(state.get().unwrap().ask, None);
+    }
+}
@@ -0,0 +1,221 @@
+//! Creation of streaming subscriptions, see
+//! <https://www.developer.saxo/openapi/learn/streaming>
+
+use super::control::HeartbeatReason;
+use super::Message;
+use crate::error::SaxoError;
+use crate::messages::common::FieldGroups;
+
+use async_trait::async_trait;
+use mockall::automock;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+/// A subscription to create on a streaming connection. It is kept by the connection, so the
+/// subscription can be recreated when the server asks for it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Subscription {
+    /// Path of the subscription end point, e.g. `port/v1/balances/subscriptions`
+    pub endpoint: &'static str,
+    pub reference_id: String,
+    pub arguments: Value,
+    pub refresh_rate: Option<u32>,
+}
+
+impl Subscription {
+    #[must_use]
+    pub fn new(endpoint: &'static str, reference_id: &str, arguments: Value) -> Self {
+        Subscription {
+            endpoint,
+            reference_id: reference_id.to_owned(),
+            arguments,
+            refresh_rate: None,
+        }
+    }
+
+    /// Subscription to the data of a client, or of a single account if `account_key` is given.
+    #[must_use]
+    pub fn for_client(
+        endpoint: &'static str,
+        reference_id: &str,
+        client_key: &str,
+        account_key: Option<&str>,
+    ) -> Self {
+        let mut arguments = json!({ "ClientKey": client_key });
+        if let Some(account_key) = account_key {
+            arguments["AccountKey"] = account_key.into();
+        }
+
+        Subscription::new(endpoint, reference_id, arguments)
+    }
+
+    /// Sets the field groups to include in the snapshot and updates.
+    #[must_use]
+    pub fn field_groups<F: Ord + Serialize>(mut self, field_groups: &FieldGroups<F>) -> Self {
+        self.arguments["FieldGroups"] = json!(field_groups);
+        self
+    }
+
+    /// Sets the minimum interval in milliseconds between updates.
+    #[must_use]
+    pub fn refresh_rate(mut self, refresh_rate: u32) -> Self {
+        self.refresh_rate = Some(refresh_rate);
+        self
+    }
+
+    /// Body of the request creating the subscription on the given context id.
+    #[must_use]
+    pub fn request<'a>(&'a self, context_id: &'a str) -> SubscriptionRequest<'a> {
+        SubscriptionRequest {
+            context_id,
+            reference_id: &self.reference_id,
+            arguments: &self.arguments,
+            refresh_rate: self.refresh_rate,
+        }
+    }
+}
+
+#[derive(Serialize, Debug, PartialEq)]
+#[serde(rename_all = "PascalCase")]
+pub struct SubscriptionRequest<'a> {
+    pub context_id: &'a str,
+    pub reference_id: &'a str,
+    pub arguments: &'a Value,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub refresh_rate: Option<u32>,
+}
+
+#[derive(Deserialize, Debug, Default, PartialEq)]
+#[serde(rename_all = "PascalCase")]
+pub struct SubscriptionResponse {
+    pub context_id: Option<String>,
+    pub format: Option<String>,
+    pub inactivity_timeout: Option<i32>,
+    pub reference_id: Option<String>,
+    pub refresh_rate: Option<i32>,
+    #[serde(default)]
+    pub snapshot: Value,
+    pub state: Option<String>,
+}
+
+/// Update delivered on the channel of a subscription.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SubscriptionUpdate {
+    /// Full snapshot replacing the previous state, sent when the subscription has been recreated
+    Snapshot(Value),
+    /// Message holding the fields that changed
+    Delta(Message),
+}
+
+/// Status of a subscription, as told by the messages and heartbeats received for it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SubscriptionStatus {
+    /// Data has been received, or the subscription has just been created
+    #[default]
+    Alive,
+    /// The subscription is alive, but there has been no new data
+    NoNewData,
+    /// No data will be sent for a while, e.g. outside trading hours
+    TemporarilyDisabled,
+    /// No data will be sent again, e.g. because of missing permissions
+    PermanentlyDisabled,
+}
+
+impl From<HeartbeatReason> for SubscriptionStatus {
+    fn from(reason: HeartbeatReason) -> Self {
+        match reason {
+            // A heartbeat is only sent when there is no new data, whatever its reason
+            HeartbeatReason::NoNewData | HeartbeatReason::Unknown => SubscriptionStatus::NoNewData,
+            HeartbeatReason::SubscriptionTemporarilyDisabled => {
+                SubscriptionStatus::TemporarilyDisabled
+            }
+            HeartbeatReason::SubscriptionPermanentlyDisabled => {
+                SubscriptionStatus::PermanentlyDisabled
+            }
+        }
+    }
+}
+
+/// Creates and deletes subscriptions on behalf of a streaming connection, and keeps its context
+/// authorized.
+#[automock]
+#[async_trait]
+pub trait Subscriber: Send + Sync {
+    async fn subscribe(
+        &self,
+        context_id: &str,
+        subscription: &Subscription,
+    ) -> Result<SubscriptionResponse, SaxoError>;
+
+    async fn unsubscribe(
+        &self,
+        context_id: &str,
+        subscription: &Subscription,
+    ) -> Result<(), SaxoError>;
+
+    /// Authorizes the streaming context with the current access token, after it was refreshed.
+    async fn authorize(&self, context_id: &str) -> Result<(), SaxoError>;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use serde_json::json;
+
+    #[test]
+    fn test_serde_subscription_request() {
+        let subscription = Subscription::new(
+            "port/v1/balances/subscriptions",
+            "balance",
+            json!({ "ClientKey": "foo" }),
+        )
+        .refresh_rate(1000);
+
+        assert_eq!(
+            serde_json::to_value(subscription.request("ctx")).unwrap(),
+            json!({
+                "ContextId": "ctx",
+                "ReferenceId": "balance",
+                "Arguments": { "ClientKey": "foo" },
+                "RefreshRate": 1000
+            })
+        );
+    }
+
+    #[test]
+    fn test_for_client() {
+        #[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+        enum FieldGroup {
+            Foo,
+        }
+
+        let client = Subscription::for_client("foo/subscriptions", "foo", "client", None);
+        let account =
+            Subscription::for_client("foo/subscriptions", "foo", "client", Some("account"))
+                .field_groups(&FieldGroups::new().with(FieldGroup::Foo));
+
+        assert_eq!(client.arguments, json!({ "ClientKey": "client" }));
+        assert_eq!(
+            account.arguments,
+            json!({ "ClientKey": "client", "AccountKey": "account", "FieldGroups": ["Foo"] })
+        );
+    }
+
+    #[test]
+    fn test_serde_subscription_response() {
+        let response = json!({
+            "ContextId": "ctx",
+            "Format": "application/json",
+            "InactivityTimeout": 120,
+            "ReferenceId": "balance",
+            "RefreshRate": 1000,
+            "Snapshot": { "CashBalance": 100.0 },
+            "State": "Active"
+        });
+
+        let response = serde_json::from_value::<SubscriptionResponse>(response).unwrap();
+        assert_eq!(response.reference_id.as_deref(), Some("balance"));
+        assert_eq!(response.snapshot, json!({ "CashBalance": 100.0 }));
+    }
+}